@@ -0,0 +1,345 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, ValueEnum};
+use compiler::{diagnostics::Diagnostic, CompileOptions, CompileOutput, Compiler, SourceMaps};
+use swc_common::{
+    errors::{ColorConfig, Handler},
+    sync::Lrc,
+    FileName, SourceFile, SourceMap,
+};
+
+const STDIO: &str = "-";
+pub(crate) const SOURCE_EXTENSION: &str = "bmr";
+pub(crate) const OUTPUT_EXTENSION: &str = "js";
+/// Extension of the client module written next to a page's render module
+pub(crate) const CLIENT_EXTENSION: &str = "client.js";
+
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Files or directories to compile, `-` reads a single file from stdin
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Output file, or output directory when compiling several files or a directory, or when
+    /// it ends with a path separator or names a directory. Writes to stdout when omitted or `-`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// How to emit source maps for the generated code
+    #[arg(long, value_enum, default_value_t = SourceMapArg::None)]
+    source_map: SourceMapArg,
+
+    /// How to report diagnostics, `json` writes one JSON object per line to stderr
+    #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+
+    /// URL the output directory is served from, pages load their client modules from it
+    #[arg(long, default_value = "/")]
+    public_url: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    Human,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SourceMapArg {
+    None,
+    /// Append the source map to the generated file as a data url
+    Inline,
+    /// Write the source map to a `.map` file next to the generated file
+    File,
+}
+
+impl From<SourceMapArg> for SourceMaps {
+    fn from(arg: SourceMapArg) -> Self {
+        match arg {
+            SourceMapArg::None => SourceMaps::None,
+            SourceMapArg::Inline => SourceMaps::Inline,
+            SourceMapArg::File => SourceMaps::File,
+        }
+    }
+}
+
+enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+struct Job {
+    source: Source,
+    // path of the output relative to the output directory
+    relative_output: PathBuf,
+}
+
+enum Output {
+    Stdout,
+    File(PathBuf),
+    Dir(PathBuf),
+}
+
+pub fn run(args: BuildArgs) -> Result<ExitCode> {
+    let (jobs, has_dir) = collect_jobs(&args.inputs)?;
+
+    let output = match args.output {
+        None => Output::Stdout,
+        Some(path) if path.as_os_str() == STDIO => Output::Stdout,
+        Some(path) if has_dir || jobs.len() > 1 || is_dir_path(&path) => Output::Dir(path),
+        Some(path) => Output::File(path),
+    };
+
+    if matches!(output, Output::Stdout) && matches!(args.source_map, SourceMapArg::File) {
+        bail!("--source-map file requires an output path");
+    }
+
+    let compiler = Compiler::default();
+    let handler = Handler::with_tty_emitter(
+        ColorConfig::Auto,
+        true,
+        false,
+        Some(compiler.source_map().clone()),
+    );
+    let report = |diagnostics: &[Diagnostic]| -> Result<()> {
+        for diagnostic in diagnostics {
+            match args.message_format {
+                MessageFormat::Human => diagnostic.emit(&handler),
+                MessageFormat::Json => {
+                    let record = diagnostic.to_record(compiler.source_map());
+                    eprintln!("{}", serde_json::to_string(&record)?);
+                }
+            }
+        }
+
+        Ok(())
+    };
+
+    let mut failed = 0;
+    let mut errors = 0;
+
+    for job in &jobs {
+        let source_file = load(compiler.source_map(), &job.source)?;
+
+        let options = CompileOptions {
+            filename: source_file.name.clone(),
+            source_maps: args.source_map.into(),
+            client_url: client_url(&args.public_url, &output, job),
+        };
+
+        match compiler.compile_file(&source_file, &options) {
+            Ok(compiled) => {
+                report(&compiled.diagnostics)?;
+
+                write(&output, job, &compiled)?;
+            }
+            Err(diagnostics) => {
+                report(&diagnostics)?;
+
+                failed += 1;
+                errors += diagnostics.iter().filter(|d| d.is_error()).count();
+            }
+        }
+    }
+
+    if failed == 0 {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // the records already describe every error
+    if args.message_format == MessageFormat::Human {
+        eprintln!(
+            "error: could not compile {} of {} {} due to {} previous {}",
+            failed,
+            jobs.len(),
+            plural(jobs.len(), "file", "files"),
+            errors,
+            plural(errors, "error", "errors"),
+        );
+    }
+
+    Ok(ExitCode::FAILURE)
+}
+
+/// `-o out/` or an existing directory names the output directory.
+fn is_dir_path(path: &Path) -> bool {
+    let path_str = path.as_os_str().to_string_lossy();
+
+    path.is_dir() || path_str.ends_with('/') || path_str.ends_with(std::path::MAIN_SEPARATOR)
+}
+
+fn collect_jobs(inputs: &[PathBuf]) -> Result<(Vec<Job>, bool)> {
+    let mut jobs = vec![];
+    let mut has_dir = false;
+
+    for input in inputs {
+        if input.as_os_str() == STDIO {
+            if inputs.len() > 1 {
+                bail!("stdin cannot be combined with other inputs");
+            }
+
+            jobs.push(Job {
+                source: Source::Stdin,
+                relative_output: PathBuf::from("stdin").with_extension(OUTPUT_EXTENSION),
+            });
+        } else if input.is_dir() {
+            has_dir = true;
+
+            let mut files = vec![];
+            walk_dir(input, &mut files)
+                .with_context(|| format!("failed to read directory {}", input.display()))?;
+            files.sort();
+
+            for file in files {
+                let relative_output = file
+                    .strip_prefix(input)
+                    .unwrap_or(&file)
+                    .with_extension(OUTPUT_EXTENSION);
+
+                jobs.push(Job {
+                    source: Source::File(file),
+                    relative_output,
+                });
+            }
+        } else if input.is_file() {
+            let file_name = input
+                .file_name()
+                .ok_or_else(|| anyhow!("{} is not a file", input.display()))?;
+
+            jobs.push(Job {
+                source: Source::File(input.to_owned()),
+                relative_output: Path::new(file_name).with_extension(OUTPUT_EXTENSION),
+            });
+        } else {
+            bail!("{} does not exist", input.display());
+        }
+    }
+
+    Ok((jobs, has_dir))
+}
+
+pub(crate) fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            walk_dir(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == SOURCE_EXTENSION) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn load(source_map: &Lrc<SourceMap>, source: &Source) -> Result<Lrc<SourceFile>> {
+    match source {
+        Source::Stdin => {
+            let mut src = String::new();
+            io::stdin()
+                .read_to_string(&mut src)
+                .context("failed to read stdin")?;
+
+            Ok(source_map.new_source_file(FileName::Custom("stdin".into()), src))
+        }
+        Source::File(path) => source_map
+            .load_file(path)
+            .with_context(|| format!("failed to read file {}", path.display())),
+    }
+}
+
+/// The URL of the client module written next to the output of `job`,
+/// `None` when writing to stdout where the client code is inlined instead.
+fn client_url(public_url: &str, output: &Output, job: &Job) -> Option<String> {
+    let relative = match output {
+        Output::Stdout => return None,
+        Output::File(path) => PathBuf::from(path.file_name()?),
+        Output::Dir(_) => job.relative_output.clone(),
+    };
+
+    Some(client_module_url(public_url, &relative))
+}
+
+/// The URL of the client module of the render module at `relative_output`
+/// in a directory served from `public_url`.
+pub(crate) fn client_module_url(public_url: &str, relative_output: &Path) -> String {
+    let relative = relative_output.with_extension(CLIENT_EXTENSION);
+    let segments: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+
+    format!(
+        "{}/{}",
+        public_url.trim_end_matches('/'),
+        segments.join("/")
+    )
+}
+
+fn write(output: &Output, job: &Job, compiled: &CompileOutput) -> Result<()> {
+    let path = match output {
+        // without a client url the render module inlines its client module
+        Output::Stdout => {
+            return io::stdout()
+                .write_all(compiled.code.as_bytes())
+                .context("failed to write to stdout")
+        }
+        Output::File(path) => path.to_owned(),
+        Output::Dir(dir) => dir.join(&job.relative_output),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+
+    write_code(&path, &compiled.code, compiled.map.as_deref())?;
+
+    if let Some(client_code) = &compiled.client_code {
+        let client_path = path.with_extension(CLIENT_EXTENSION);
+
+        write_code(&client_path, client_code, compiled.client_map.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Writes `code` to `path`, and its source map to a `.map` file next to it.
+fn write_code(path: &Path, code: &str, map: Option<&str>) -> Result<()> {
+    let code = match map {
+        Some(map) => {
+            let mut map_path = path.to_owned().into_os_string();
+            map_path.push(".map");
+            let map_path = PathBuf::from(map_path);
+
+            fs::write(&map_path, map)
+                .with_context(|| format!("failed to write file {}", map_path.display()))?;
+
+            format!(
+                "{}//# sourceMappingURL={}\n",
+                code,
+                map_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default()
+            )
+        }
+        None => code.to_owned(),
+    };
+
+    fs::write(path, code).with_context(|| format!("failed to write file {}", path.display()))
+}
+
+fn plural<'a>(n: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if n == 1 {
+        singular
+    } else {
+        plural
+    }
+}
@@ -0,0 +1,511 @@
+mod http;
+mod overlay;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use compiler::{CompileOptions, Compiler, SourceMaps};
+use notify::{RecursiveMode, Watcher};
+use swc_common::{
+    errors::{ColorConfig, Handler},
+    FileName,
+};
+
+use crate::build::{
+    client_module_url, walk_dir, CLIENT_EXTENSION, OUTPUT_EXTENSION, SOURCE_EXTENSION,
+};
+
+use self::{
+    http::ChunkedResponse,
+    overlay::{error_overlay, LIVE_RELOAD_SCRIPT},
+};
+
+// Streams the chunks returned by `render` the same way as
+// `netlify/edge-functions/render.js`
+const RENDER_SCRIPT: &str = r#"
+import { pathToFileURL } from "node:url";
+
+const { render } = await import(pathToFileURL(process.argv[1]).href);
+
+const stream = (chunks) => {
+  for (const chunk of chunks) {
+    if (Array.isArray(chunk)) {
+      stream(chunk);
+    } else {
+      process.stdout.write(String(chunk));
+    }
+  }
+};
+
+stream(render());
+"#;
+
+// how long to wait for more file system events before rebuilding
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Args)]
+pub struct DevArgs {
+    /// Project directory to watch for Boomer files
+    #[arg(default_value = ".")]
+    dir: PathBuf,
+
+    /// Port the dev server listens on
+    #[arg(short, long, default_value_t = 3000)]
+    port: u16,
+
+    /// Directory the compiled pages are written to, defaults to `<dir>/.boomer`
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+
+    /// Node.js executable used to render pages
+    #[arg(long, default_value = "node")]
+    runtime: String,
+}
+
+enum Page {
+    Compiled(PathBuf),
+    Failed(String),
+}
+
+struct DevServer {
+    dir: PathBuf,
+    out_dir: PathBuf,
+    runtime: String,
+    // keyed by the path of the Boomer file relative to `dir`
+    pages: Mutex<BTreeMap<PathBuf, Page>>,
+    // browsers listening for reloads
+    clients: Mutex<Vec<TcpStream>>,
+}
+
+pub fn run(args: DevArgs) -> Result<()> {
+    let dir = args
+        .dir
+        .canonicalize()
+        .with_context(|| format!("{} does not exist", args.dir.display()))?;
+    let out_dir = args.out_dir.unwrap_or_else(|| dir.join(".boomer"));
+
+    fs::create_dir_all(&out_dir)
+        .with_context(|| format!("failed to create directory {}", out_dir.display()))?;
+    // the compiled pages are ES modules
+    fs::write(out_dir.join("package.json"), r#"{ "type": "module" }"#)
+        .context("failed to write package.json")?;
+    let out_dir = out_dir.canonicalize()?;
+
+    let server = Arc::new(DevServer {
+        dir,
+        out_dir,
+        runtime: args.runtime,
+        pages: Default::default(),
+        clients: Default::default(),
+    });
+
+    let mut sources = vec![];
+    walk_dir(&server.dir, &mut sources)
+        .with_context(|| format!("failed to read directory {}", server.dir.display()))?;
+
+    for source in &sources {
+        server.compile(source);
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("failed to listen on port {}", args.port))?;
+
+    eprintln!(
+        "boomer dev server running at http://localhost:{}",
+        args.port
+    );
+
+    {
+        let server = server.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let server = server.clone();
+                thread::spawn(move || server.handle(stream));
+            }
+        });
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&server.dir, RecursiveMode::Recursive)?;
+
+    while let Ok(event) = rx.recv() {
+        let mut changed = vec![];
+        let mut event = Some(event);
+
+        while let Some(next) = event {
+            if let Ok(next) = next {
+                changed.extend(next.paths);
+            }
+            event = rx.recv_timeout(DEBOUNCE).ok();
+        }
+
+        changed.sort();
+        changed.dedup();
+
+        let changed: Vec<PathBuf> = changed
+            .into_iter()
+            .filter(|path| {
+                !path.starts_with(&server.out_dir)
+                    && path.extension().is_some_and(|ext| ext == SOURCE_EXTENSION)
+            })
+            .collect();
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        for path in &changed {
+            if path.is_file() {
+                server.compile(path);
+            } else {
+                server.remove(path);
+            }
+        }
+
+        server.reload();
+    }
+
+    Ok(())
+}
+
+impl DevServer {
+    fn relative<'a>(&self, source: &'a Path) -> &'a Path {
+        source.strip_prefix(&self.dir).unwrap_or(source)
+    }
+
+    fn compile(&self, source: &Path) {
+        let relative = self.relative(source).to_owned();
+
+        let page = match self.try_compile(source, &relative) {
+            Ok(page) => page,
+            Err(e) => Page::Failed(format!("{:#}", e)),
+        };
+
+        if let Page::Failed(_) = page {
+            eprintln!("failed to compile {}", relative.display());
+        } else {
+            eprintln!("compiled {}", relative.display());
+        }
+
+        self.pages.lock().unwrap().insert(relative, page);
+    }
+
+    fn try_compile(&self, source: &Path, relative: &Path) -> Result<Page> {
+        let compiler = Compiler::default();
+        let source_file = compiler
+            .source_map()
+            .load_file(source)
+            .with_context(|| format!("failed to read file {}", source.display()))?;
+
+        let options = CompileOptions {
+            filename: FileName::Real(relative.to_owned()),
+            source_maps: SourceMaps::Inline,
+            client_url: Some(client_module_url(
+                "/",
+                &relative.with_extension(OUTPUT_EXTENSION),
+            )),
+        };
+
+        let tty = Handler::with_tty_emitter(
+            ColorConfig::Auto,
+            true,
+            false,
+            Some(compiler.source_map().clone()),
+        );
+
+        match compiler.compile_file(&source_file, &options) {
+            Ok(compiled) => {
+                for diagnostic in &compiled.diagnostics {
+                    diagnostic.emit(&tty);
+                }
+
+                let output = self.out_dir.join(relative).with_extension(OUTPUT_EXTENSION);
+
+                if let Some(parent) = output.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&output, compiled.code)
+                    .with_context(|| format!("failed to write file {}", output.display()))?;
+
+                let client_output = output.with_extension(CLIENT_EXTENSION);
+                match compiled.client_code {
+                    Some(client_code) => {
+                        fs::write(&client_output, client_code).with_context(|| {
+                            format!("failed to write file {}", client_output.display())
+                        })?
+                    }
+                    None => {
+                        let _ = fs::remove_file(client_output);
+                    }
+                }
+
+                Ok(Page::Compiled(output))
+            }
+            Err(diagnostics) => {
+                let buffer = Buffer::default();
+                let handler = Handler::with_emitter_writer(
+                    Box::new(buffer.clone()),
+                    Some(compiler.source_map().clone()),
+                );
+
+                for diagnostic in &diagnostics {
+                    diagnostic.emit(&tty);
+                    diagnostic.emit(&handler);
+                }
+
+                Ok(Page::Failed(buffer.into_string()))
+            }
+        }
+    }
+
+    fn remove(&self, source: &Path) {
+        let relative = self.relative(source);
+
+        if let Some(Page::Compiled(output)) = self.pages.lock().unwrap().remove(relative) {
+            let _ = fs::remove_file(output.with_extension(CLIENT_EXTENSION));
+            let _ = fs::remove_file(output);
+        }
+    }
+
+    fn reload(&self) {
+        self.clients
+            .lock()
+            .unwrap()
+            .retain_mut(|client| http::send_event(client, "reload").is_ok());
+    }
+
+    fn handle(&self, mut stream: TcpStream) {
+        let request = match http::read_request(&stream) {
+            Ok(request) => request,
+            Err(_) => return,
+        };
+
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/__boomer/events") => self.subscribe(stream),
+            ("GET", path) if path.ends_with(CLIENT_EXTENSION) => {
+                self.client_module(&mut stream, path)
+            }
+            ("GET", path) => self.render(&mut stream, path),
+            _ => http::respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain",
+                b"method not allowed",
+            ),
+        };
+
+        if let Err(e) = result {
+            eprintln!("failed to respond to {}: {}", request.path, e);
+        }
+    }
+
+    fn subscribe(&self, mut stream: TcpStream) -> io::Result<()> {
+        http::start_event_stream(&mut stream)?;
+        self.clients.lock().unwrap().push(stream);
+        Ok(())
+    }
+
+    /// Serves the client module loaded by a compiled page.
+    fn client_module(&self, stream: &mut TcpStream, path: &str) -> io::Result<()> {
+        let relative = Path::new(path.trim_start_matches('/'));
+
+        let code = if relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            fs::read(self.out_dir.join(relative)).ok()
+        } else {
+            None
+        };
+
+        match code {
+            Some(code) => http::respond(stream, "200 OK", "text/javascript", &code),
+            None => http::respond(stream, "404 Not Found", "text/plain", b"not found"),
+        }
+    }
+
+    fn render(&self, stream: &mut TcpStream, path: &str) -> io::Result<()> {
+        let page = {
+            let pages = self.pages.lock().unwrap();
+
+            route(path)
+                .into_iter()
+                .find_map(|source| match pages.get(&source) {
+                    Some(Page::Compiled(output)) => Some(Ok(output.to_owned())),
+                    Some(Page::Failed(diagnostics)) => Some(Err((source, diagnostics.to_owned()))),
+                    None => None,
+                })
+        };
+
+        let output = match page {
+            Some(Ok(output)) => output,
+            Some(Err((source, diagnostics))) => {
+                let title = format!("Failed to compile {}", source.display());
+                let body = format!(
+                    "{}{}",
+                    error_overlay(&title, &diagnostics),
+                    LIVE_RELOAD_SCRIPT
+                );
+
+                return http::respond(
+                    stream,
+                    "500 Internal Server Error",
+                    "text/html",
+                    body.as_bytes(),
+                );
+            }
+            None => {
+                let body = format!("<p>No page found for {}</p>{}", path, LIVE_RELOAD_SCRIPT);
+
+                return http::respond(stream, "404 Not Found", "text/html", body.as_bytes());
+            }
+        };
+
+        let mut child = Command::new(&self.runtime)
+            .args([
+                "--enable-source-maps",
+                "--input-type=module",
+                "-e",
+                RENDER_SCRIPT,
+                "--",
+            ])
+            .arg(&output)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // drained while stdout streams, a render filling the stderr pipe
+        // would otherwise block before closing stdout
+        let stderr = child.stderr.take().map(|mut child_stderr| {
+            thread::spawn(move || {
+                let mut stderr = String::new();
+                let _ = child_stderr.read_to_string(&mut stderr);
+                stderr
+            })
+        });
+
+        let streamed = ChunkedResponse::start(stream, "200 OK", "text/html; charset=utf-8")
+            .and_then(|mut response| {
+                if let Some(stdout) = child.stdout.take() {
+                    stream_chunks(stdout, &mut response)?;
+                }
+                Ok(response)
+            });
+
+        // the child is reaped even when the client went away
+        if streamed.is_err() {
+            let _ = child.kill();
+        }
+        let status = child.wait()?;
+        let stderr = stderr
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default();
+
+        let mut response = streamed?;
+
+        if !status.success() {
+            response.send(error_overlay("Failed to render page", &stderr).as_bytes())?;
+        }
+
+        response.send(LIVE_RELOAD_SCRIPT.as_bytes())?;
+        response.finish()
+    }
+}
+
+fn stream_chunks(mut reader: impl Read, response: &mut ChunkedResponse) -> io::Result<()> {
+    let mut buf = [0; 8192];
+
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            return Ok(());
+        }
+        response.send(&buf[..read])?;
+    }
+}
+
+/// The Boomer files that can render `path`, e.g. `/about` is rendered
+/// by `about.bmr` or `about/index.bmr`.
+fn route(path: &str) -> Vec<PathBuf> {
+    let path = Path::new(path.trim_start_matches('/'));
+
+    if path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return vec![];
+    }
+
+    let index = path.join("index").with_extension(SOURCE_EXTENSION);
+
+    if path.as_os_str().is_empty() || path.to_string_lossy().ends_with('/') {
+        vec![index]
+    } else {
+        vec![path.with_extension(SOURCE_EXTENSION), index]
+    }
+}
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    fn into_string(self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::route;
+
+    #[test]
+    fn it_routes_the_root_to_the_index_page() {
+        assert_eq!(route("/"), vec![PathBuf::from("index.bmr")]);
+    }
+
+    #[test]
+    fn it_routes_a_trailing_slash_to_the_directory_index() {
+        assert_eq!(route("/blog/"), vec![PathBuf::from("blog/index.bmr")]);
+    }
+
+    #[test]
+    fn it_routes_nested_paths_to_a_page_or_its_index() {
+        assert_eq!(
+            route("/blog/first-post"),
+            vec![
+                PathBuf::from("blog/first-post.bmr"),
+                PathBuf::from("blog/first-post/index.bmr")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_does_not_route_outside_the_pages() {
+        assert!(route("/../secret").is_empty());
+    }
+}
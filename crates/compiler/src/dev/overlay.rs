@@ -0,0 +1,38 @@
+//! Markup injected into pages served by the dev server.
+
+/// Reloads the page when the dev server reports a rebuild.
+pub const LIVE_RELOAD_SCRIPT: &str = r#"<script type="module">
+  const events = new EventSource("/__boomer/events");
+  events.onmessage = (event) => {
+    if (event.data === "reload") location.reload();
+  };
+</script>"#;
+
+pub fn error_overlay(title: &str, details: &str) -> String {
+    format!(
+        r#"<div id="__boomer-overlay" style="position:fixed;inset:0;z-index:2147483647;overflow:auto;margin:0;padding:32px;background:rgba(20,20,20,.95);color:#eee;font:14px/1.5 ui-monospace,Menlo,monospace">
+  <h2 style="margin:0 0 16px;color:#ff5555;font-size:18px">{}</h2>
+  <pre style="margin:0;white-space:pre-wrap">{}</pre>
+  <p style="margin:16px 0 0;color:#999">Fix the error and save, the page will reload automatically.</p>
+</div>"#,
+        escape_html(title),
+        escape_html(details)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+use swc_common::{errors::Handler, SourceMap, Span, Spanned};
+
+use crate::parser::ParserError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Name of the `SyntaxError` variant that raised the diagnostic
+    pub code: Cow<'static, str>,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn emit(&self, handler: &Handler) {
+        let mut db = match self.severity {
+            Severity::Error => handler.struct_err(&self.message),
+            Severity::Warning => handler.struct_warn(&self.message),
+        };
+
+        db.set_span(self.span);
+        db.emit();
+    }
+
+    /// Resolves the span against `source_map` into a record that can be
+    /// serialized for editors and CI, see `--message-format=json`.
+    pub fn to_record(&self, source_map: &SourceMap) -> DiagnosticRecord {
+        let (file, start, end) = if self.span.is_dummy() {
+            (None, None, None)
+        } else {
+            let lo = source_map.lookup_char_pos(self.span.lo);
+            let hi = source_map.lookup_char_pos(self.span.hi);
+
+            (
+                Some(lo.file.name.to_string()),
+                Some(Position {
+                    line: lo.line,
+                    column: lo.col.0 + 1,
+                }),
+                Some(Position {
+                    line: hi.line,
+                    column: hi.col.0 + 1,
+                }),
+            )
+        };
+
+        DiagnosticRecord {
+            severity: self.severity,
+            code: self.code.to_string(),
+            message: self.message.clone(),
+            file,
+            start,
+            end,
+        }
+    }
+}
+
+/// One based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticRecord {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub start: Option<Position>,
+    pub end: Option<Position>,
+}
+
+impl From<ParserError> for Diagnostic {
+    fn from(error: ParserError) -> Self {
+        let span = error.span();
+        let kind = error.into_kind();
+
+        Diagnostic {
+            severity: Severity::Error,
+            code: kind.code().into(),
+            message: kind.msg().into_owned(),
+            span,
+        }
+    }
+}
@@ -0,0 +1,1394 @@
+pub mod diagnostics;
+pub mod parser;
+#[allow(dead_code)] // experimental, not wired into the pipeline yet
+mod reactive_script;
+mod runtime;
+pub mod transform;
+
+use swc_common::{sync::Lrc, BytePos, FileName, LineCol, SourceFile, SourceMap};
+use swc_ecma_ast::Module;
+use swc_ecma_codegen::{
+    text_writer::{JsWriter, WriteJs},
+    Emitter,
+};
+
+use crate::{
+    diagnostics::Diagnostic,
+    parser::{BmrParser, ParseResult},
+    transform::BmrTransform,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceMaps {
+    #[default]
+    None,
+    /// Append the source map to the generated code as a base64 data url
+    Inline,
+    /// Return the source map in [CompileOutput::map] so it can be written next to the code
+    File,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// Name of the file being compiled, used when reporting diagnostics
+    /// and as the source of the generated source map
+    pub filename: FileName,
+    pub source_maps: SourceMaps,
+    /// URL pages load [CompileOutput::client_code] from, the code is
+    /// inlined into the page when `None`
+    pub client_url: Option<String>,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            filename: FileName::Anon,
+            source_maps: SourceMaps::None,
+            client_url: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CompileOutput {
+    /// The generated server render module
+    pub code: String,
+    /// The v3 source map of [CompileOutput::code] when [SourceMaps::File] is requested
+    pub map: Option<String>,
+    /// The generated browser module, when the file has client code or client only imports
+    pub client_code: Option<String>,
+    /// The v3 source map of [CompileOutput::client_code] when [SourceMaps::File] is requested
+    pub client_map: Option<String>,
+    /// Warnings raised while compiling, errors fail the compilation
+    pub diagnostics: Vec<Diagnostic>,
+    pub parse_result: ParseResult,
+}
+
+#[derive(Debug)]
+pub struct ParseOutput {
+    /// Partial when [ParseOutput::diagnostics] contains errors
+    pub parse_result: ParseResult,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parses a single Boomer file without compiling it. Unlike [compile] this
+/// returns a result for broken files too, so tooling can keep working on them.
+pub fn parse(source: &str, options: CompileOptions) -> ParseOutput {
+    let compiler = Compiler::default();
+    let source_file = compiler
+        .source_map
+        .new_source_file(options.filename, source.into());
+
+    compiler.parse_file(&source_file)
+}
+
+/// Compiles a single Boomer file into a server render module.
+pub fn compile(source: &str, options: CompileOptions) -> Result<CompileOutput, Vec<Diagnostic>> {
+    let compiler = Compiler::default();
+    let source_file = compiler
+        .source_map
+        .new_source_file(options.filename.clone(), source.into());
+
+    compiler.compile_file(&source_file, &options)
+}
+
+/// Tells the markers and payload of the Boomer files rendered into one page
+/// apart, the FNV-1a hash of the file's name.
+fn module_id(filename: &FileName) -> String {
+    let hash = filename
+        .to_string()
+        .bytes()
+        .fold(0x811c9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+        });
+
+    format!("{:08x}", hash)
+}
+
+/// Compiles files that share a [SourceMap], so that the spans of the returned
+/// diagnostics can be rendered by a [swc_common::errors::Handler] using the same map.
+#[derive(Default)]
+pub struct Compiler {
+    source_map: Lrc<SourceMap>,
+}
+
+impl Compiler {
+    pub fn new(source_map: Lrc<SourceMap>) -> Self {
+        Self { source_map }
+    }
+
+    pub fn source_map(&self) -> &Lrc<SourceMap> {
+        &self.source_map
+    }
+
+    pub fn parse_file(&self, source_file: &SourceFile) -> ParseOutput {
+        let mut bmr_parser = BmrParser::default();
+
+        let parse_result = bmr_parser.parse_file(source_file);
+
+        ParseOutput {
+            parse_result,
+            diagnostics: bmr_parser
+                .take_errors()
+                .into_iter()
+                .map(Diagnostic::from)
+                .collect(),
+        }
+    }
+
+    pub fn compile_file(
+        &self,
+        source_file: &SourceFile,
+        options: &CompileOptions,
+    ) -> Result<CompileOutput, Vec<Diagnostic>> {
+        let ParseOutput {
+            parse_result,
+            diagnostics,
+        } = self.parse_file(source_file);
+
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return Err(diagnostics);
+        }
+
+        let mut client_map = None;
+        let mut transformed = BmrTransform::transform(
+            &parse_result,
+            &module_id(&options.filename),
+            options.client_url.as_deref(),
+            |client| {
+                let (code, map) = self.emit(client, options.source_maps);
+                client_map = map;
+                code
+            },
+        );
+
+        let mut diagnostics = diagnostics;
+        diagnostics.extend(transformed.errors.drain(..).map(Diagnostic::from));
+
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return Err(diagnostics);
+        }
+
+        let (code, map) = self.emit(&transformed.server, options.source_maps);
+
+        Ok(CompileOutput {
+            code,
+            map,
+            client_code: transformed.client_code,
+            client_map,
+            diagnostics,
+            parse_result,
+        })
+    }
+
+    /// Generates the code of `module` along with its source map when
+    /// [SourceMaps::File] is requested.
+    fn emit(&self, module: &Module, source_maps: SourceMaps) -> (String, Option<String>) {
+        let mut buf = vec![];
+        let mut mappings: Vec<(BytePos, LineCol)> = vec![];
+
+        let srcmap = match source_maps {
+            SourceMaps::None => None,
+            SourceMaps::Inline | SourceMaps::File => Some(&mut mappings),
+        };
+
+        let wr: Box<dyn WriteJs> = Box::new(JsWriter::new(
+            self.source_map.clone(),
+            "\n",
+            &mut buf,
+            srcmap,
+        ));
+
+        Emitter {
+            cfg: swc_ecma_codegen::Config { minify: false },
+            cm: self.source_map.clone(),
+            comments: None,
+            wr,
+        }
+        .emit_module(module)
+        .expect("writing to a Vec<u8> cannot fail");
+
+        let mut code = String::from_utf8_lossy(&buf).to_string();
+
+        let map = match source_maps {
+            SourceMaps::None => None,
+            SourceMaps::Inline => {
+                code.push_str(&format!(
+                    "//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}\n",
+                    base64::encode(self.build_source_map(&mut mappings))
+                ));
+                None
+            }
+            SourceMaps::File => Some(self.build_source_map(&mut mappings)),
+        };
+
+        (code, map)
+    }
+
+    fn build_source_map(&self, mappings: &mut Vec<(BytePos, LineCol)>) -> String {
+        let mut map = vec![];
+
+        self.source_map
+            .build_source_map(mappings)
+            .to_writer(&mut map)
+            .expect("writing to a Vec<u8> cannot fail");
+
+        String::from_utf8_lossy(&map).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use swc_common::FileName;
+
+    use super::{compile, module_id, parse, parser::JSXRoot, CompileOptions, Compiler, SourceMaps};
+
+    #[test]
+    fn it_compiles_to_a_render_module() {
+        let output = compile(
+            r#"
+server: {
+  const hello = "Hello";
+}
+
+<h1>{hello}</h1>;
+"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.diagnostics.is_empty());
+        assert!(output.parse_result.server.is_some());
+        assert!(output.code.contains("const hello = \"Hello\";"));
+        assert!(output.code.contains("export function render(props = {})"));
+    }
+
+    #[test]
+    fn it_returns_diagnostics_on_error() {
+        let diagnostics = compile("server: 5;\n<div></div>;", CompileOptions::default())
+            .expect_err("expected compile to fail");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "the server label is not for a block statement"
+        );
+    }
+
+    #[test]
+    fn it_generates_source_maps() {
+        let output = compile(
+            "<h1>{hello}</h1>;",
+            CompileOptions {
+                filename: FileName::Real("page.bmr".into()),
+                source_maps: SourceMaps::File,
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        let map = sourcemap::SourceMap::from_slice(output.map.expect("missing map").as_bytes())
+            .expect("invalid source map");
+
+        assert_eq!(map.get_source(0), Some("page.bmr"));
+        assert!(map.get_token_count() > 0);
+    }
+
+    #[test]
+    fn it_generates_source_maps_of_the_client_module() {
+        let output = compile(
+            r#"client: {
+  const [count, setCount] = useState(0);
+}
+
+<button onClick={() => setCount(count() + 1)}>{count()}</button>;"#,
+            CompileOptions {
+                filename: FileName::Real("page.bmr".into()),
+                source_maps: SourceMaps::File,
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        let map = output.client_map.expect("missing client map");
+        let map = sourcemap::SourceMap::from_slice(map.as_bytes()).expect("invalid source map");
+
+        assert_eq!(map.get_source(0), Some("page.bmr"));
+        assert!(map.get_token_count() > 0);
+
+        let output = compile(
+            "client: { console.log(1); }\n<p></p>;",
+            CompileOptions {
+                source_maps: SourceMaps::Inline,
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .client_code
+            .expect("missing client code")
+            .contains("//# sourceMappingURL=data:application/json;charset=utf-8;base64,"));
+    }
+
+    #[test]
+    fn it_resolves_diagnostic_records() {
+        let compiler = Compiler::default();
+        let source_file = compiler.source_map().new_source_file(
+            FileName::Real("page.bmr".into()),
+            "<div></div>;\n  foo: {}".into(),
+        );
+
+        let diagnostics = compiler
+            .compile_file(&source_file, &CompileOptions::default())
+            .expect_err("expected compile to fail");
+
+        let record = diagnostics[0].to_record(compiler.source_map());
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"severity":"error","code":"UnexpectedLabeledStatement","message":"foo is not a valid labeled block","file":"page.bmr","start":{"line":2,"column":3},"end":{"line":2,"column":6}}"#
+        );
+    }
+
+    #[test]
+    fn it_reports_a_missing_jsx_root() {
+        let diagnostics =
+            compile("server: {}", CompileOptions::default()).expect_err("expected compile to fail");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "MissingJSXRoot");
+    }
+
+    #[test]
+    fn it_reports_all_errors_with_a_partial_parse_result() {
+        let output = parse(
+            r#"
+server: {
+  function a() {}
+  function a() {}
+}
+
+foo: {}
+"#,
+            CompileOptions::default(),
+        );
+
+        let codes: Vec<&str> = output.diagnostics.iter().map(|d| &*d.code).collect();
+
+        assert_eq!(
+            codes,
+            [
+                "ServerFunctionRedeclared",
+                "UnexpectedLabeledStatement",
+                "MissingJSXRoot"
+            ]
+        );
+        assert!(output.parse_result.server.is_some());
+        assert!(output.parse_result.jsx.is_none());
+    }
+
+    #[test]
+    fn it_reports_invalid_syntax() {
+        let output = parse("<div>{</div>;", CompileOptions::default());
+
+        assert_eq!(output.diagnostics[0].code, "InvalidSyntax");
+        assert!(output.parse_result.jsx.is_none());
+    }
+
+    #[test]
+    fn it_splits_imports_between_server_and_client() {
+        let output = compile(
+            r#"
+import { db, type Db } from "./db.js";
+import { format } from "./format.js";
+import confetti from "confetti";
+import type { User } from "./user";
+import { type Settings } from "./settings";
+import "./styles.css";
+
+export const prerender = true;
+export type { Db };
+
+server: {
+  const user = db.user();
+}
+
+client: {
+  const [count, setCount] = useState(0);
+  confetti(format(count()));
+}
+
+<h1>{format(user.name)}</h1>;
+"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.starts_with(
+            r#"import { db } from "./db.js";
+import { format } from "./format.js";
+import "./styles.css";
+"#
+        ));
+        assert!(output.code.contains(
+            r#"const user = db.user();
+export const prerender = true;
+"#
+        ));
+        assert!(!output.code.contains("Db"));
+        assert!(!output.code.contains("Settings"));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.starts_with(
+            r#"import { format } from "./format.js";
+import confetti from "confetti";
+"#
+        ));
+        assert!(client_code.contains(
+            r#"const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0"#
+        ));
+    }
+
+    #[test]
+    fn it_reports_server_functions_used_by_the_client() {
+        let diagnostics = compile(
+            r#"server: {
+  function format(value) {
+    return value.toFixed(2);
+  }
+  class Cart {}
+  const { total } = { total: 2 };
+}
+
+client: {
+  const [count, setCount] = useState(total);
+  console.log(new Cart());
+}
+
+<p>
+  {format(count())}
+  <button onClick={() => setCount((format) => format + 1)}>+</button>
+</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+        assert_eq!(
+            codes,
+            vec!["UnresumableServerBinding", "UnresumableServerBinding"]
+        );
+    }
+
+    #[test]
+    fn it_reports_handlers_reading_server_scopes() {
+        let diagnostics = compile(
+            r#"server: {
+  const items = ["a", "b"];
+
+  function Item({ label }) {
+    return <li onClick={() => alert(label)}>{label}</li>;
+  }
+}
+
+client: {
+  const [selected, setSelected] = useState(null);
+}
+
+<ul>
+  {items.map((item) => (
+    <li onClick={() => setSelected(item)}>{item}</li>
+  ))}
+  {items.map((label) => <Item label={label} />)}
+  <li onClick={(item) => setSelected(items.indexOf(item))}>first</li>
+  <li onClick={() => console.log(props.id)}>props</li>
+</ul>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        assert!(diagnostics.iter().all(|d| d.code == "ServerScopedBinding"));
+        let mut names: Vec<_> = diagnostics
+            .iter()
+            .filter_map(|d| d.message.split(' ').next())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["item", "label", "props"]);
+    }
+
+    #[test]
+    fn it_reports_holes_reading_server_scopes() {
+        let diagnostics = compile(
+            r#"server: {
+  const items = ["a", "b"];
+  const label = "Count";
+}
+
+client: {
+  const [count, setCount] = useState(0);
+}
+
+<main>
+  <h1 title={label + count()}>{count() > 2 && <b>{label}</b>}</h1>
+  <p>{props.title} {props.title + count()}</p>
+  <ul>
+    {items.map((item) => <li title={item + count()}>{item}</li>)}
+  </ul>
+  <button onClick={(label) => setCount(label.length)}>+</button>
+</main>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        assert!(diagnostics.iter().all(|d| d.code == "ServerScopedBinding"));
+        let mut names: Vec<_> = diagnostics
+            .iter()
+            .filter_map(|d| d.message.split(' ').next())
+            .collect();
+        names.sort();
+        // the loop is in the hole that reads `count`
+        assert_eq!(names, vec!["props"]);
+    }
+
+    #[test]
+    fn it_ends_pages_with_the_payload_of_the_client() {
+        let output = compile(
+            r#"server: {
+  const user = { name: "Ada" };
+}
+
+client: {
+  const [count, setCount] = useState(0);
+  console.log(user.name);
+}
+
+<h1>{count()}</h1>;"#,
+            CompileOptions {
+                client_url: Some("/counter.client.js".into()),
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        // documents rendering the page as a component load its client module too
+        assert!(output.code.contains(
+            r#"        "</h1>",
+        $$serialize("00c6c235", $$states, {
+            user
+        }),
+        '<script type="module" src="/counter.client.js"></script>'
+    ]);"#
+        ));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.contains(r#"const user = $$resume("00c6c235", "values", "user");"#));
+    }
+
+    #[test]
+    fn it_imports_what_handlers_use_in_the_client_module() {
+        let output = compile(
+            r#"import confetti from "confetti";
+
+<button onClick={() => confetti()}>Party</button>;"#,
+            CompileOptions {
+                client_url: Some("/party.client.js".into()),
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        assert!(!output.code.contains("confetti"));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.starts_with("import confetti from \"confetti\";\n"));
+    }
+
+    #[test]
+    fn it_imports_what_holes_use_in_both_modules() {
+        let output = compile(
+            r#"import { format } from "./format.js";
+
+client: {
+  const [count, setCount] = useState(0);
+}
+
+<p>{format(count())}</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .code
+            .starts_with("import { format } from \"./format.js\";\n"));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.starts_with("import { format } from \"./format.js\";\n"));
+        assert!(client_code.contains(r#"$$bindText("00c6c235:0", ()=>$$child(format(count()))"#));
+    }
+
+    #[test]
+    fn it_renders_attributes() {
+        let output = compile(
+            r#"<a className="btn &amp; more" htmlFor="x" href={url} tabIndex={2} hidden disabled={false}></a>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .code
+            .contains(r#"'<a class="btn &amp; more" for="x"',"#));
+        assert!(output.code.contains(r#"$$attr("href", url),"#));
+        assert!(output.code.contains(r#"' tabIndex="2" hidden>',"#));
+        assert!(output.code.contains("function $$attr(name, value)"));
+        assert!(output.code.contains("function $$escape(value)"));
+    }
+
+    #[test]
+    fn it_escapes_children() {
+        let output = compile(
+            r#"<p>a &amp; b {name} {"<b>"}</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(r#""a &amp; b ","#));
+        assert!(output.code.contains("$$child(name),"));
+        assert!(output.code.contains(r#"" &lt;b&gt;""#));
+        assert!(output.code.contains("function $$child(value)"));
+        assert!(output.code.contains("function $$escape(value)"));
+    }
+
+    #[test]
+    fn it_renders_inner_html_unescaped() {
+        let output = compile(
+            r#"<div className="post" innerHTML={html}>ignored</div>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(r#"'<div class="post">',"#));
+        assert!(output.code.contains("$$raw(html)"));
+        assert!(!output.code.contains("ignored"));
+        assert!(!output.code.contains("$$child"));
+    }
+
+    #[test]
+    fn it_keeps_blank_lines_of_preformatted_text() {
+        for (input, text) in [
+            ("<pre>a\r\n\r\nb</pre>;", r#""a\r\n\r\nb""#),
+            ("<pre>a\n\nb</pre>;", r#""a\n\nb""#),
+            ("<pre>a\r\rb</pre>;", r#""a\r\rb""#),
+        ] {
+            let output = compile(input, CompileOptions::default()).expect("failed to compile");
+
+            assert!(output.code.contains(text), "{}", output.code);
+        }
+    }
+
+    #[test]
+    fn it_renders_nested_elements_in_order() {
+        let output = compile(
+            "<ul>\n  <li>a</li>\r\n  <li>{b}</li>\n</ul>;",
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"        "<ul>",
+        [
+            "<li>",
+            [
+                "a"
+            ],
+            "</li>",
+            "<li>",
+            [
+                $$child(b)
+            ],
+            "</li>"
+        ],
+        "</ul>""#
+        ));
+    }
+
+    #[test]
+    fn it_reports_void_elements_with_children() {
+        let diagnostics = compile(
+            "<p><br /><img>\n</img><input>text</input></p>;",
+            CompileOptions::default(),
+        )
+        .expect_err("should fail to compile");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "VoidElementWithChildren");
+        assert_eq!(
+            diagnostics[0].message,
+            "<input> is a void element and cannot have children"
+        );
+    }
+
+    #[test]
+    fn it_renders_a_fragment_root() {
+        let output = compile(
+            "<><header></header><main><><p>a</p></></main></>;",
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(matches!(
+            output.parse_result.jsx,
+            Some(JSXRoot::Fragment(_))
+        ));
+        assert!(output.code.contains(
+            r#"    return $$markup([
+        "<header>",
+        [],
+        "</header>",
+        "<main>",
+        [
+            "<p>",
+            [
+                "a"
+            ],
+            "</p>"
+        ],
+        "</main>"
+    ]);"#
+        ));
+    }
+
+    #[test]
+    fn it_reports_more_than_one_jsx_root() {
+        let output = parse("<head></head>;\n<></>;", CompileOptions::default());
+
+        assert_eq!(output.diagnostics.len(), 1);
+        assert_eq!(output.diagnostics[0].code, "MoreThanOneJSXRoot");
+    }
+
+    #[test]
+    fn it_renders_components_with_props() {
+        let output = compile(
+            r#"
+import Header from "./header.bmr";
+
+server: {
+  function Card({ title }) {
+    return <h2>{title}</h2>;
+  }
+}
+
+<main><Header title="a &amp; b" /><Card title={name} featured /></main>;
+"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .code
+            .contains(r#"import { render as Header } from "./header.js";"#));
+        assert!(output.code.contains(
+            r#"function Card({ title  }) {
+    return $$markup([
+        "<h2>",
+        [
+            $$child(title)
+        ],
+        "</h2>"
+    ]);
+}"#
+        ));
+        assert!(output.code.contains(
+            r#"            $$child(Header({
+                title: "a & b"
+            })),
+            $$child(Card({
+                title: name,
+                featured: true
+            }))"#
+        ));
+        assert!(!output.code.contains("export default"));
+    }
+
+    #[test]
+    fn it_reports_unresolved_components() {
+        let diagnostics = compile("<main><Header /></main>;", CompileOptions::default())
+            .expect_err("should fail to compile");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "UnresolvedComponent");
+
+        // data is not a component, only through a member expression
+        let diagnostics = compile(
+            r#"server: {
+  const Title = "Posts";
+  const Icons = { Star: () => "*" };
+  const Card = ({ title }) => <h2>{title}</h2>;
+}
+
+<main><Title /><Icons.Star /><Card title={Title} /></main>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("should fail to compile");
+
+        let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+        assert_eq!(codes, vec!["UnresolvedComponent"]);
+        assert!(diagnostics[0].message.contains("<Title>"));
+    }
+
+    #[test]
+    fn it_passes_children_and_slots_to_components() {
+        let output = compile(
+            r#"
+import Layout from "./layout.bmr";
+
+<Layout>
+  <h1 slot="header">Title</h1>
+  <p>Body</p>
+</Layout>;
+"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"        $$child(Layout({
+            children: $$markup([
+                "<p>",
+                [
+                    "Body"
+                ],
+                "</p>"
+            ]),
+            slots: {
+                header: $$markup([
+                    "<h1>",
+                    [
+                        "Title"
+                    ],
+                    "</h1>"
+                ])
+            }
+        }))"#
+        ));
+    }
+
+    #[test]
+    fn it_renders_slots() {
+        let output = compile(
+            r#"<main><slot name="header">Untitled</slot><slot /></main>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"$$slot(props, "header", $$markup([
+                "Untitled"
+            ])),
+            $$slot(props, null)"#
+        ));
+        assert!(output
+            .code
+            .contains("function $$slot(props, name, fallback)"));
+    }
+
+    #[test]
+    fn it_compiles_jsx_inside_expressions() {
+        let output = compile(
+            "<ul>{items.map((item) => <li>{item}</li>)}{empty && <p>none</p>}</ul>;",
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"$$child(items.map((item)=>$$markup([
+                    "<li>",
+                    [
+                        $$child(item)
+                    ],
+                    "</li>"
+                ])
+            )),
+            $$child(empty && $$markup([
+                "<p>",
+                [
+                    "none"
+                ],
+                "</p>"
+            ]))"#
+        ));
+    }
+
+    #[test]
+    fn it_compiles_spread_attributes_and_children() {
+        let output = compile(
+            "<ul class=\"list\" {...attrs} id=\"last\">{...items}</ul>;",
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        // merged in source order for the last value to win
+        assert!(output.code.contains(
+            r#""<ul",
+        $$spreadAttrs({
+            class: "list",
+            ...attrs,
+            id: "last"
+        }),
+        ">",
+        [
+            ...$$child(items)
+        ],"#
+        ));
+        assert!(output.code.contains("function $$spreadAttrs(attrs)"));
+    }
+
+    #[test]
+    fn it_renders_namespaced_and_member_expression_names() {
+        let output = compile(
+            r##"
+import * as Icons from "./icons.js";
+
+<svg><use xlink:href="#star" /><svg:rect /><Icons.Star size={16} /></svg>;
+"##,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(r##"'<use xlink:href="#star">',"##));
+        assert!(output.code.contains(r#""<svg:rect>","#));
+        assert!(output.code.contains("Icons.Star({"));
+
+        let diagnostics = compile("<Icons.Star />;", CompileOptions::default())
+            .expect_err("should fail to compile");
+
+        assert_eq!(diagnostics[0].code, "UnresolvedComponent");
+        assert_eq!(
+            diagnostics[0].message,
+            "<Icons.Star> is not a component, import it or declare it in the server block"
+        );
+    }
+
+    #[test]
+    fn it_folds_style_and_class_objects() {
+        let output = compile(
+            r#"<p style={{ marginTop: 4, lineHeight: 1.5, color: "red" }} className={["a", { b: true, c: false }]}></p>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .code
+            .contains(r#"'<p style="margin-top:4px;line-height:1.5;color:red" class="a b">',"#));
+        assert!(!output.code.contains("$$style"));
+
+        let output = compile(
+            r#"<p style={{ width }} className={["a", { b: on }]}></p>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(r#"$$attr("style", $$style({"#));
+        assert!(output.code.contains(
+            r#"$$attr("class", $$class([
+            "a",
+            {
+                b: on
+            }
+        ])),"#
+        ));
+    }
+
+    #[test]
+    fn it_renders_documents() {
+        let output = compile(
+            r#"client: { console.log("</script>"); }
+
+<html>
+  <body>
+    <h1>Hello</h1>
+  </body>
+</html>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains("return $$markup($$document(["));
+        // a document without a head still gets one for the page head content
+        assert!(output.code.contains(r#"$$region("head", [])"#));
+        assert!(output.code.contains(r#"$$region("body", ["#));
+        assert!(output
+            .code
+            .contains(r#"'<script type="module">console.log("<\\/script>");\n</script>'"#));
+
+        let output = compile(
+            "<div><head><title>Hello</title></head></div>;",
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains("$$head(["));
+        assert!(!output.code.contains("$$document"));
+        assert!(!output.code.contains("<head>"));
+    }
+
+    #[test]
+    fn it_lifts_event_handlers_to_the_client() {
+        let output = compile(
+            r#"client: {
+  const [count, setCount] = useState(0);
+}
+
+<div>
+  <button onClick={() => setCount(count() + 1)} onDoubleClick={reset} class="btn">+</button>
+  <a href="/" onclick="track()">Home</a>
+</div>;"#,
+            CompileOptions {
+                client_url: Some("/counter.client.js".into()),
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"'<button data-bmr-click="00c6c235:0" data-bmr-dblclick="00c6c235:1" class="btn">',"#
+        ));
+        assert!(output.code.contains(r#"'<a href="/" onclick="track()">',"#));
+        assert!(!output.code.contains("setCount(count() + 1)"));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.contains("const $$handlers = [\n    ()=>setCount(count() + 1)\n"));
+        assert!(client_code.contains(
+            r#"$$delegate("00c6c235", [
+    "click",
+    "dblclick"
+], $$handlers);"#
+        ));
+    }
+
+    #[test]
+    fn it_namespaces_markers_by_module() {
+        let source = r#"client: {
+  const [count, setCount] = useState(0);
+}
+
+<button onClick={() => setCount(count() + 1)}>{count()}</button>;"#;
+        let compile_as = |name: &str| {
+            compile(
+                source,
+                CompileOptions {
+                    filename: FileName::Real(name.into()),
+                    ..Default::default()
+                },
+            )
+            .expect("failed to compile")
+        };
+
+        let counter = compile_as("counter.bmr");
+        let other = compile_as("other.bmr");
+
+        let id = module_id(&FileName::Real("counter.bmr".into()));
+        assert_ne!(id, module_id(&FileName::Real("other.bmr".into())));
+        assert!(counter
+            .code
+            .contains(&format!(r#"data-bmr-click="{}:0""#, id)));
+        assert!(!other.code.contains(&format!(r#""{}:0""#, id)));
+        assert!(counter
+            .client_code
+            .expect("missing client code")
+            .contains(&format!(r#"$$delegate("{}", ["#, id)));
+    }
+
+    #[test]
+    fn it_binds_state_to_reactive_holes() {
+        let output = compile(
+            r#"client: {
+  const [count, setCount] = useState(0);
+}
+
+<p class={count() > 1 ? "many" : "one"}>{count()} clicks</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        // every render creates its own state
+        assert!(output.code.contains(
+            r#"export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>0"#
+        ));
+        assert!(output.code.contains(r#"'<p data-bmr-hole="00c6c235:0"',"#));
+        assert!(output.code.contains(
+            r#""<!--bmr:00c6c235:1-->",
+            $$child(count()),
+            "<!--/bmr:00c6c235:1--> clicks""#
+        ));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.contains(r#"$$bindText("00c6c235:1", ()=>$$child(count())"#));
+        assert!(client_code.contains(
+            r#"$$bindAttr("00c6c235:0", "class", ()=>$$class(count() > 1 ? "many" : "one")"#
+        ));
+    }
+
+    #[test]
+    fn it_reports_state_read_in_raw_text_elements() {
+        for tag in ["title", "textarea", "script", "style"] {
+            let diagnostics = compile(
+                &format!(
+                    r#"client: {{
+  const [count, setCount] = useState(0);
+}}
+
+<div>
+  <{0}>{{count()}} clicks</{0}>
+  <p title={{count()}}>{{count()}}</p>
+</div>;"#,
+                    tag
+                ),
+                CompileOptions::default(),
+            )
+            .expect_err("expected compile to fail");
+
+            let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+            assert_eq!(codes, vec!["StateInRawText"], "in <{}>", tag);
+            assert!(diagnostics[0].message.starts_with(&format!("<{}>", tag)));
+        }
+    }
+
+    #[test]
+    fn it_reports_client_names_read_by_the_server() {
+        let diagnostics = compile(
+            r#"server: {
+  function Label() {
+    return <b>{label}</b>;
+  }
+}
+
+client: {
+  const [count, setCount] = useState(0);
+  const label = "Count";
+  const double = () => count() * 2;
+  function reset() {
+    setCount(0);
+  }
+}
+
+<p title={label}>
+  <Label />
+  {double()} {count()}
+  <button onClick={reset}>Reset</button>
+</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        assert!(diagnostics.iter().all(|d| d.code == "ClientOnlyBinding"));
+        let mut names: Vec<_> = diagnostics
+            .iter()
+            .filter_map(|d| d.message.split(' ').next())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["double", "label"]);
+    }
+
+    #[test]
+    fn it_reports_state_read_outside_of_holes() {
+        let diagnostics = compile(
+            r#"server: {
+  function Count() {
+    return <span>{count()}</span>;
+  }
+
+  function Card({ value }) {
+    return <b>{value}</b>;
+  }
+}
+
+client: {
+  const [count, setCount] = useState(0);
+}
+
+<div>
+  <Count />
+  <Card value={count()} />
+</div>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+        assert_eq!(codes, vec!["StaticStateRead", "StaticStateRead"]);
+    }
+
+    #[test]
+    fn it_reports_state_shadowing_server_bindings() {
+        let diagnostics = compile(
+            r#"import { title } from "./site.js";
+
+server: {
+  const count = 1;
+}
+
+client: {
+  const [count, setCount] = useState(0);
+  const [title, setTitle] = useState("");
+}
+
+<p>{count()} {title()}</p>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+        assert_eq!(
+            codes,
+            vec!["StateShadowsServerBinding", "StateShadowsServerBinding"]
+        );
+    }
+
+    #[test]
+    fn it_binds_state_declared_without_destructuring() {
+        let output = compile(
+            r#"client: {
+  const state = useState(0);
+  const [, setName] = useState("Ada");
+  const { 0: step } = useState(2);
+  const clicks = state[0];
+}
+
+<button onClick={() => state[1](clicks() + step())}>{state[0]()} and {clicks()}</button>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains("const clicks = state[0];\n"));
+        assert!(output.code.contains("$$child(state[0]())"));
+        assert!(output.code.contains("$$child(clicks())"));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.contains(r#"$$bindText("00c6c235:0", ()=>$$child(state[0]())"#));
+        assert!(client_code.contains(r#"$$bindText("00c6c235:1", ()=>$$child(clicks())"#));
+    }
+
+    #[test]
+    fn it_reports_invalid_state_declarations() {
+        let diagnostics = compile(
+            r#"client: {
+  const [count, setCount, extra] = useState(0);
+  const [{ value }] = useState({ value: 1 });
+  const state = useState(0);
+  console.log(useState(1), state.length);
+  let x = useState(2);
+  x = 5;
+  const reset = () => {
+    let read = state[0];
+  };
+}
+
+<p></p>;"#,
+            CompileOptions::default(),
+        )
+        .expect_err("expected compile to fail");
+
+        let codes: Vec<_> = diagnostics.iter().map(|d| &*d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "InvalidStateDeclaration",
+                "InvalidStateDeclaration",
+                "InvalidStateDeclaration",
+                "InvalidStateAccess",
+                "InvalidStateDeclaration",
+                "InvalidStateDeclaration"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_loads_the_client_module_of_documents_from_its_url() {
+        let output = compile(
+            r#"client: { console.log("hello"); }
+
+<html><body></body></html>;"#,
+            CompileOptions {
+                client_url: Some("/pages/index.client.js".into()),
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        assert!(output
+            .code
+            .contains(r#"'<script type="module" src="/pages/index.client.js"></script>'"#));
+        assert!(!output.code.contains("hello"));
+        assert_eq!(
+            output.client_code.as_deref(),
+            Some("console.log(\"hello\");\n")
+        );
+    }
+
+    #[test]
+    fn it_loads_the_client_module_of_components() {
+        let source = r#"import confetti from "confetti";
+
+<button onClick={() => confetti()}>Party</button>;"#;
+
+        let output = compile(
+            source,
+            CompileOptions {
+                client_url: Some("/components/party.client.js".into()),
+                ..Default::default()
+            },
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"        "</button>",
+        '<script type="module" src="/components/party.client.js"></script>'
+    ]);"#
+        ));
+
+        let output = compile(source, CompileOptions::default()).expect("failed to compile");
+
+        assert!(output
+            .code
+            .contains(r#"'<script type="module">import confetti from "confetti";"#));
+    }
+
+    #[test]
+    fn it_serializes_state_and_server_values_for_the_client() {
+        let output = compile(
+            r#"server: {
+  const step = 2;
+  const title = "not used by the client";
+}
+
+client: {
+  const [count, setCount] = useState(10);
+}
+
+<html>
+  <body>
+    <h1>{title}</h1>
+    <button onClick={() => setCount(count() + step)}>+</button>
+  </body>
+</html>;"#,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile");
+
+        assert!(output.code.contains(
+            r#"$$serialize("00c6c235", $$states, {
+            step
+        }),"#
+        ));
+
+        let client_code = output.client_code.expect("missing client code");
+        assert!(client_code.contains(r#"const step = $$resume("00c6c235", "values", "step");"#));
+        assert!(client_code.contains(
+            r#"const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>10"#
+        ));
+        assert!(!client_code.contains("title"));
+    }
+}
@@ -1,81 +1,37 @@
-mod parser;
-mod transform;
-mod reactive_script;
+mod build;
+mod dev;
 
-use crate::{parser::BmrParser, transform::BmrTransform};
-use std::path::Path;
-use swc_common::{
-    errors::{ColorConfig, Handler},
-    sync::Lrc,
-    SourceMap,
-};
-use swc_ecma_codegen::{
-    text_writer::{JsWriter, WriteJs},
-    Emitter,
-};
-use swc_ecma_parser::{
-    lexer::Lexer, Capturing, Parser as SWCParser, StringInput, Syntax, TsConfig,
-};
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
 
-fn main() {
-    println!("Hello, Boomer!");
-    let source_map: Lrc<SourceMap> = Default::default();
-    let handler =
-        Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(source_map.clone()));
-
-    let source_file = source_map
-        .load_file(Path::new("./test_data/input.js"))
-        .expect("failed to read file");
-
-    let lexer = Lexer::new(
-        Syntax::Typescript(TsConfig {
-            tsx: true,
-            ..Default::default()
-        }),
-        Default::default(),
-        StringInput::from(&*source_file),
-        None,
-    );
-
-    let mut swc_parser = SWCParser::new_from(Capturing::new(lexer));
-
-    for e in swc_parser.take_errors() {
-        e.into_diagnostic(&handler).emit();
-    }
-
-    let module = swc_parser
-        .parse_module()
-        .map_err(|e| e.into_diagnostic(&handler).emit())
-        .expect("failed to parse your boomer file 😞");
-
-    let mut bmr_parser = BmrParser::default();
-
-    let result = bmr_parser
-        .parse(module)
-        .expect("failed to parse your boomer file 😞");
-
-    for e in bmr_parser.take_errors() {
-        e.into_diagnostic(&handler).emit();
-    }
+#[derive(Parser)]
+#[command(name = "boomer", version, about = "The Boomer compiler")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    let transformed = BmrTransform::transform(result);
+#[derive(Subcommand)]
+enum Command {
+    /// Compile Boomer files into server render modules
+    Build(build::BuildArgs),
+    /// Watch a project, recompiling on change, and serve its pages with live reload
+    Dev(dev::DevArgs),
+}
 
-    let mut buf = vec![];
+fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    let wr: Box<dyn WriteJs> = Box::new(JsWriter::new(source_map.clone(), "\n", &mut buf, None));
+    let result = match cli.command {
+        Command::Build(args) => build::run(args),
+        Command::Dev(args) => dev::run(args).map(|_| ExitCode::SUCCESS),
+    };
 
-    Emitter {
-        cfg: swc_ecma_codegen::Config { minify: false },
-        cm: source_map.clone(),
-        comments: None,
-        wr,
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
     }
-    .emit_module(&transformed)
-    .unwrap();
-
-    std::fs::write(
-        "./test_data/output.js",
-        String::from_utf8_lossy(&buf).to_string(),
-    )
-    .expect("failed to write file");
 }
@@ -6,14 +6,18 @@ use std::{
 use swc_atoms::JsWord;
 use swc_common::{
     errors::{DiagnosticBuilder, Handler},
-    Span, Spanned,
+    SourceFile, Span, Spanned,
 };
 use swc_ecma_ast::{
-    BlockStmt, Callee, Expr, FnDecl, JSXElement, Module, ModuleDecl, ModuleItem, Pat, Stmt,
-    VarDeclarator,
+    BlockStmt, Callee, ComputedPropName, Expr, FnDecl, JSXElement, JSXFragment, JSXText,
+    KeyValuePatProp, Lit, MemberExpr, MemberProp, Module, ModuleDecl, ModuleItem, Number,
+    ObjectPatProp, Pat, PropName, Stmt, Str, VarDecl, VarDeclKind, VarDeclarator,
+};
+use swc_ecma_parser::{
+    lexer::Lexer, Capturing, Parser as SWCParser, StringInput, Syntax, TsConfig,
 };
 
-use swc_ecma_visit::{Visit, VisitWith};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParserError {
@@ -39,7 +43,7 @@ impl ParserError {
 
     #[cold]
     #[inline(never)]
-    pub fn into_diagnostic(self, handler: &Handler) -> DiagnosticBuilder {
+    pub fn into_diagnostic(self, handler: &Handler) -> DiagnosticBuilder<'_> {
         let span = self.span();
 
         let kind = self.into_kind();
@@ -59,11 +63,47 @@ pub enum SyntaxError {
     LabeledServerIsNotBlock,
     LabeledClientIsNotBlock,
     MoreThanOneJSXRoot,
+    MissingJSXRoot,
     UnexpectedTopLevelStatement,
     ServerFunctionRedeclared(JsWord),
+    VoidElementWithChildren(JsWord),
+    UnresolvedComponent(JsWord),
+    StaticStateRead(JsWord),
+    InvalidStateDeclaration,
+    InvalidStateAccess(JsWord),
+    StateShadowsServerBinding(JsWord),
+    UnresumableServerBinding(JsWord),
+    ServerScopedBinding(JsWord),
+    StateInRawText(JsWord),
+    ClientOnlyBinding(JsWord),
+    InvalidSyntax(swc_ecma_parser::error::SyntaxError),
 }
 
 impl SyntaxError {
+    /// Stable identifier of the error, safe to match on in tooling.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SyntaxError::UnexpectedLabeledStatement(_) => "UnexpectedLabeledStatement",
+            SyntaxError::LabeledServerIsNotBlock => "LabeledServerIsNotBlock",
+            SyntaxError::LabeledClientIsNotBlock => "LabeledClientIsNotBlock",
+            SyntaxError::MoreThanOneJSXRoot => "MoreThanOneJSXRoot",
+            SyntaxError::MissingJSXRoot => "MissingJSXRoot",
+            SyntaxError::UnexpectedTopLevelStatement => "UnexpectedTopLevelStatement",
+            SyntaxError::ServerFunctionRedeclared(_) => "ServerFunctionRedeclared",
+            SyntaxError::VoidElementWithChildren(_) => "VoidElementWithChildren",
+            SyntaxError::UnresolvedComponent(_) => "UnresolvedComponent",
+            SyntaxError::StaticStateRead(_) => "StaticStateRead",
+            SyntaxError::InvalidStateDeclaration => "InvalidStateDeclaration",
+            SyntaxError::InvalidStateAccess(_) => "InvalidStateAccess",
+            SyntaxError::StateShadowsServerBinding(_) => "StateShadowsServerBinding",
+            SyntaxError::UnresumableServerBinding(_) => "UnresumableServerBinding",
+            SyntaxError::ServerScopedBinding(_) => "ServerScopedBinding",
+            SyntaxError::StateInRawText(_) => "StateInRawText",
+            SyntaxError::ClientOnlyBinding(_) => "ClientOnlyBinding",
+            SyntaxError::InvalidSyntax(_) => "InvalidSyntax",
+        }
+    }
+
     #[cold]
     #[inline(never)]
     pub fn msg(&self) -> Cow<'static, str> {
@@ -77,13 +117,65 @@ impl SyntaxError {
             SyntaxError::LabeledClientIsNotBlock => {
                 "the client label is not for a block statement".into()
             }
-            SyntaxError::MoreThanOneJSXRoot => "only one JSX root permitted per file".into(),
+            SyntaxError::MoreThanOneJSXRoot => {
+                "only one JSX root permitted per file, wrap sibling elements in a fragment <>...</>"
+                    .into()
+            }
+            SyntaxError::MissingJSXRoot => "a boomer file must have a JSX root element".into(),
             SyntaxError::UnexpectedTopLevelStatement => "unexpected top level statement".into(),
             SyntaxError::ServerFunctionRedeclared(word) => format!(
                 "server functions can only be declared once, {} has multiple declarations",
                 word
             )
             .into(),
+            SyntaxError::VoidElementWithChildren(tag) => {
+                format!("<{}> is a void element and cannot have children", tag).into()
+            }
+            SyntaxError::UnresolvedComponent(name) => format!(
+                "<{}> is not a component, import it or declare it in the server block",
+                name
+            )
+            .into(),
+            SyntaxError::StaticStateRead(getter) => format!(
+                "{}() reads client state here, which cannot update, state is only reactive in the text and attributes of the page markup",
+                getter
+            )
+            .into(),
+            SyntaxError::InvalidStateDeclaration => {
+                "useState must be declared with const and assigned to a name or destructured to identifiers, e.g. const [count, setCount] = useState(0)"
+                    .into()
+            }
+            SyntaxError::InvalidStateAccess(tuple) => format!(
+                "{0} holds state, read it with {0}[0]() and set it with {0}[1](value)",
+                tuple
+            )
+            .into(),
+            SyntaxError::StateShadowsServerBinding(name) => format!(
+                "{} is client state and a top level name of the server block or an import, rename one of them",
+                name
+            )
+            .into(),
+            SyntaxError::UnresumableServerBinding(name) => format!(
+                "{} is a function or class of the server block, the client can only resume values, declare it in the client block",
+                name
+            )
+            .into(),
+            SyntaxError::ServerScopedBinding(name) => format!(
+                "{} is declared in the markup or a function of the server block, client code can only read the top level names of the server and client blocks",
+                name
+            )
+            .into(),
+            SyntaxError::StateInRawText(tag) => format!(
+                "<{}> renders its children as text, which cannot be updated when the state read here is set",
+                tag
+            )
+            .into(),
+            SyntaxError::ClientOnlyBinding(name) => format!(
+                "{} is declared in the client block, which does not run on the server, only its state can be read by the markup",
+                name
+            )
+            .into(),
+            SyntaxError::InvalidSyntax(error) => error.msg(),
         }
     }
 }
@@ -96,7 +188,7 @@ pub struct FunctionDeclaration {
 #[derive(Debug)]
 pub struct ServerBlock {
     pub block: BlockStmt,
-    _function_declarations: HashMap<JsWord, FunctionDeclaration>,
+    pub function_declarations: HashMap<JsWord, FunctionDeclaration>,
 }
 
 #[derive(Debug)]
@@ -105,12 +197,44 @@ pub struct ClientBlock {
     pub use_state: UseStateDeclarations,
 }
 
-#[derive(Debug)]
+/// The parts of a Boomer file. When the file has errors this is partial,
+/// holding whatever could be recovered.
+#[derive(Debug, Default)]
 pub struct ParseResult {
     pub declarations: Vec<ModuleDecl>,
     pub server: Option<ServerBlock>,
     pub client: Option<ClientBlock>,
-    pub jsx: JSXElement,
+    pub jsx: Option<JSXRoot>,
+}
+
+/// The markup of a Boomer file, a fragment when the page has more than one
+/// top level element.
+#[derive(Debug, Clone)]
+pub enum JSXRoot {
+    Element(JSXElement),
+    Fragment(JSXFragment),
+}
+
+impl Spanned for JSXRoot {
+    fn span(&self) -> Span {
+        match self {
+            JSXRoot::Element(element) => element.span,
+            JSXRoot::Fragment(fragment) => fragment.span,
+        }
+    }
+}
+
+impl<V: ?Sized + Visit> VisitWith<V> for JSXRoot {
+    fn visit_with(&self, v: &mut V) {
+        self.visit_children_with(v)
+    }
+
+    fn visit_children_with(&self, v: &mut V) {
+        match self {
+            JSXRoot::Element(element) => v.visit_jsx_element(element),
+            JSXRoot::Fragment(fragment) => v.visit_jsx_fragment(fragment),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -119,11 +243,43 @@ pub struct BmrParser {
 }
 
 impl BmrParser {
-    pub fn parse(&mut self, module: Module) -> anyhow::Result<ParseResult> {
+    pub fn parse_file(&mut self, source_file: &SourceFile) -> ParseResult {
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsConfig {
+                tsx: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::from(source_file),
+            None,
+        );
+
+        let mut swc_parser = SWCParser::new_from(Capturing::new(lexer));
+
+        let module = swc_parser.parse_module();
+
+        for e in swc_parser.take_errors() {
+            self.emit_error(e.span(), SyntaxError::InvalidSyntax(e.into_kind()));
+        }
+
+        match module {
+            Ok(mut module) => {
+                module.visit_mut_with(&mut JSXTextLineTerminators);
+                self.parse(module)
+            }
+            Err(e) => {
+                self.emit_error(e.span(), SyntaxError::InvalidSyntax(e.into_kind()));
+                ParseResult::default()
+            }
+        }
+    }
+
+    pub fn parse(&mut self, module: Module) -> ParseResult {
+        let module_span = module.span;
         let mut declarations = vec![];
         let mut server: Option<ServerBlock> = None;
         let mut client: Option<ClientBlock> = None;
-        let mut jsx: Option<JSXElement> = None;
+        let mut jsx: Option<JSXRoot> = None;
 
         for module_item in module.body {
             match module_item {
@@ -140,7 +296,7 @@ impl BmrParser {
 
                                 server = Some(ServerBlock {
                                     block,
-                                    _function_declarations: visitor.function_declarations,
+                                    function_declarations: visitor.function_declarations,
                                 });
                             } else {
                                 self.emit_error(l.span, SyntaxError::LabeledServerIsNotBlock);
@@ -152,6 +308,8 @@ impl BmrParser {
 
                                 visitor.visit_block_stmt(&block);
 
+                                self.errors.append(visitor.take_errors().as_mut());
+
                                 client = Some(ClientBlock {
                                     block,
                                     use_state: visitor.use_state,
@@ -168,12 +326,16 @@ impl BmrParser {
                         }
                     },
                     Stmt::Expr(e) => {
-                        if let Expr::JSXElement(jsx_el) = *e.expr {
-                            if jsx.is_some() {
-                                self.emit_error(e.span, SyntaxError::MoreThanOneJSXRoot);
-                            } else {
-                                jsx = Some(*jsx_el);
-                            }
+                        let root = match *e.expr {
+                            Expr::JSXElement(jsx_el) => JSXRoot::Element(*jsx_el),
+                            Expr::JSXFragment(jsx_frag) => JSXRoot::Fragment(jsx_frag),
+                            _ => continue,
+                        };
+
+                        if jsx.is_some() {
+                            self.emit_error(e.span, SyntaxError::MoreThanOneJSXRoot);
+                        } else {
+                            jsx = Some(root);
                         }
                     }
                     _ => self.emit_error(s.span(), SyntaxError::UnexpectedTopLevelStatement),
@@ -181,16 +343,16 @@ impl BmrParser {
             }
         }
 
-        let jsx = jsx.expect("Missing JSX Element");
+        if jsx.is_none() {
+            self.emit_error(module_span, SyntaxError::MissingJSXRoot);
+        }
 
-        let result = ParseResult {
+        ParseResult {
             declarations,
             server,
             client,
             jsx,
-        };
-
-        Ok(result)
+        }
     }
 
     #[cold]
@@ -199,8 +361,8 @@ impl BmrParser {
         self.errors.push(ParserError::new(span, error));
     }
 
-    pub fn take_errors(&self) -> Vec<ParserError> {
-        self.errors.to_owned()
+    pub fn take_errors(&mut self) -> Vec<ParserError> {
+        std::mem::take(&mut self.errors)
     }
 }
 
@@ -215,8 +377,8 @@ impl ServerVisitor {
         ServerVisitor::default()
     }
 
-    fn take_errors(&self) -> Vec<ParserError> {
-        self.errors.to_owned()
+    fn take_errors(&mut self) -> Vec<ParserError> {
+        std::mem::take(&mut self.errors)
     }
 }
 
@@ -242,52 +404,280 @@ impl Visit for ServerVisitor {
     }
 }
 
+/// The names bound to the state of `useState` calls in the client block.
 #[derive(Debug, Default)]
 pub struct UseStateDeclarations {
+    /// `count` in `const [count, setCount] = useState(0)`
     pub get: HashSet<JsWord>,
+    /// `setCount` in `const [count, setCount] = useState(0)`
     pub set: HashSet<JsWord>,
+    /// `state` in `const state = useState(0)`, read with `state[0]()` and
+    /// set with `state[1](value)`
+    pub tuples: HashSet<JsWord>,
+}
+
+impl UseStateDeclarations {
+    /// The getter `callee` calls, `count` or `state[0]`.
+    pub fn getter(&self, callee: &Expr) -> Option<JsWord> {
+        match callee {
+            Expr::Ident(id) if self.get.contains(&id.sym) => Some(id.sym.clone()),
+            _ => match self.tuple_index(callee) {
+                Some((tuple, 0)) => Some(format!("{}[0]", tuple).into()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Whether `callee` is a setter, `setCount` or `state[1]`.
+    pub fn is_setter(&self, callee: &Expr) -> bool {
+        match callee {
+            Expr::Ident(id) => self.set.contains(&id.sym),
+            _ => matches!(self.tuple_index(callee), Some((_, 1))),
+        }
+    }
+
+    /// Whether `init` creates state or aliases it, `useState(0)`, `count` or
+    /// `state[0]`.
+    pub fn is_state(&self, init: &Expr) -> bool {
+        match init {
+            Expr::Ident(id) => {
+                self.get.contains(&id.sym)
+                    || self.set.contains(&id.sym)
+                    || self.tuples.contains(&id.sym)
+            }
+            _ => is_use_state_call(init) || self.tuple_index(init).is_some(),
+        }
+    }
+
+    /// `("state", 1)` for `state[1]`.
+    fn tuple_index(&self, expr: &Expr) -> Option<(JsWord, u8)> {
+        match expr {
+            Expr::Member(member) => self.tuple_member(member),
+            _ => None,
+        }
+    }
+
+    fn tuple_member(&self, member: &MemberExpr) -> Option<(JsWord, u8)> {
+        let tuple = match &*member.obj {
+            Expr::Ident(id) if self.tuples.contains(&id.sym) => id.sym.clone(),
+            _ => return None,
+        };
+
+        match &member.prop {
+            MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                Expr::Lit(Lit::Num(Number { value, .. })) if *value == 0.0 => Some((tuple, 0)),
+                Expr::Lit(Lit::Num(Number { value, .. })) if *value == 1.0 => Some((tuple, 1)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn is_use_state_call(expr: &Expr) -> bool {
+    matches!(expr, Expr::Call(call) if is_use_state_callee(&call.callee))
+}
+
+fn is_use_state_callee(callee: &Callee) -> bool {
+    match callee {
+        Callee::Expr(callee) => matches!(&**callee, Expr::Ident(id) if &*id.sym == "useState"),
+        _ => false,
+    }
 }
 
 #[derive(Default)]
 struct ClientVisitor {
     use_state: UseStateDeclarations,
+    /// Whether the declarators visited are `const`
+    in_const: bool,
+    errors: Vec<ParserError>,
 }
 
 impl ClientVisitor {
-    fn record_use_state_declaration(&mut self, decl: &VarDeclarator) {
-        if let Pat::Array(arr) = &decl.name {
-            match arr.elems.len() {
-                2 => {
-                    let get = arr.elems[0].as_ref();
-                    let set = arr.elems[1].as_ref();
-
-                    match (get, set) {
-                        (Some(Pat::Ident(get)), Some(Pat::Ident(set))) => {
-                            self.use_state.get.insert(get.id.sym.clone());
-                            self.use_state.set.insert(set.id.sym.clone());
+    fn take_errors(&mut self) -> Vec<ParserError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn is_alias(&self, init: &Expr) -> bool {
+        matches!(init, Expr::Ident(id) if self.use_state.tuples.contains(&id.sym))
+            || self.use_state.getter(init).is_some()
+            || self.use_state.is_setter(init)
+    }
+
+    /// Records the names `pat` binds to the `[get, set]` pair of a
+    /// `useState` call, or of a name holding one.
+    fn record_use_state_declaration(&mut self, pat: &Pat) {
+        match pat {
+            Pat::Ident(tuple) => {
+                self.use_state.tuples.insert(tuple.id.sym.clone());
+            }
+            // `[count, setCount]`, `[count]` or `[, setCount]`
+            Pat::Array(arr) if arr.elems.len() <= 2 => {
+                for (index, elem) in arr.elems.iter().enumerate() {
+                    if let Some(elem) = elem {
+                        self.record_use_state_element(elem, index);
+                    }
+                }
+            }
+            // `{ 0: count, 1: setCount }`
+            Pat::Object(obj) => {
+                for prop in &obj.props {
+                    let index = match prop {
+                        ObjectPatProp::KeyValue(KeyValuePatProp { key, .. }) => match key {
+                            PropName::Num(Number { value, .. }) => Some(*value),
+                            PropName::Str(Str { value, .. }) => value.parse().ok(),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+
+                    match (prop, index) {
+                        (ObjectPatProp::KeyValue(KeyValuePatProp { value, .. }), Some(index))
+                            if index == 0.0 || index == 1.0 =>
+                        {
+                            self.record_use_state_element(value, index as usize)
                         }
-                        _ => (), // TODO: throw error
+                        _ => self.emit_error(prop.span(), SyntaxError::InvalidStateDeclaration),
                     }
                 }
-                _ => (), // TODO: throw error
             }
+            _ => self.emit_error(pat.span(), SyntaxError::InvalidStateDeclaration),
         }
-        // TODO: handle other ways of initialising (e.g. const a = useState(0))
+    }
+
+    fn record_use_state_element(&mut self, pat: &Pat, index: usize) {
+        let names = match index {
+            0 => &mut self.use_state.get,
+            _ => &mut self.use_state.set,
+        };
+
+        match pat {
+            Pat::Ident(id) => {
+                names.insert(id.id.sym.clone());
+            }
+            _ => self.emit_error(pat.span(), SyntaxError::InvalidStateDeclaration),
+        }
+    }
+
+    /// `const read = count`, `const count = state[0]` or
+    /// `const [count, setCount] = state` alias state declared before.
+    fn record_alias(&mut self, decl: &VarDeclarator, init: &Expr) {
+        if let Expr::Ident(id) = init {
+            if self.use_state.tuples.contains(&id.sym) {
+                return self.record_use_state_declaration(&decl.name);
+            }
+        }
+
+        let names = if self.use_state.getter(init).is_some() {
+            &mut self.use_state.get
+        } else if self.use_state.is_setter(init) {
+            &mut self.use_state.set
+        } else {
+            return;
+        };
+
+        match &decl.name {
+            Pat::Ident(id) => {
+                names.insert(id.id.sym.clone());
+            }
+            _ => self.emit_error(decl.name.span(), SyntaxError::InvalidStateDeclaration),
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn emit_error(&mut self, span: Span, error: SyntaxError) {
+        self.errors.push(ParserError::new(span, error));
     }
 }
 
 impl Visit for ClientVisitor {
+    fn visit_var_decl(&mut self, var: &VarDecl) {
+        let in_const = std::mem::replace(&mut self.in_const, var.kind == VarDeclKind::Const);
+        var.visit_children_with(self);
+        self.in_const = in_const;
+    }
+
     fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
-        if let Some(init) = &decl.init {
-            if let Expr::Call(call) = &**init {
-                if let Callee::Expr(e) = &call.callee {
-                    if let Expr::Ident(id) = &**e {
-                        if &*id.sym == "useState" {
-                            self.record_use_state_declaration(decl);
-                        }
-                    }
-                }
+        let binds_state = match decl.init.as_deref() {
+            Some(Expr::Call(call)) => is_use_state_callee(&call.callee),
+            Some(init) => self.is_alias(init),
+            None => false,
+        };
+        if binds_state && !self.in_const {
+            // assigning the names later would disconnect them from the state
+            self.emit_error(decl.span, SyntaxError::InvalidStateDeclaration);
+        }
+
+        match decl.init.as_deref() {
+            Some(Expr::Call(call)) if is_use_state_callee(&call.callee) => {
+                self.record_use_state_declaration(&decl.name);
+                // the call is bound, its arguments may hold more state
+                call.args.visit_with(self);
+            }
+            Some(init) => {
+                self.record_alias(decl, init);
+                init.visit_with(self);
             }
+            None => (),
         }
     }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if is_use_state_call(expr) {
+            // state that is not bound to names cannot be read or set
+            self.emit_error(expr.span(), SyntaxError::InvalidStateDeclaration);
+        }
+
+        expr.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let Expr::Ident(id) = &*member.obj {
+            if self.use_state.tuples.contains(&id.sym)
+                && self.use_state.tuple_member(member).is_none()
+            {
+                self.emit_error(member.span, SyntaxError::InvalidStateAccess(id.sym.clone()));
+            }
+        }
+
+        member.visit_children_with(self);
+    }
+}
+
+/// The swc lexer reads every line terminator in JSX text twice, `"a\nb"`
+/// is lexed as `"a\n\nb"`, `"a\r\nb"` as `"a\n\r\nb"` and `"a\rb"` as
+/// `"a\r\rb"`.
+struct JSXTextLineTerminators;
+
+impl VisitMut for JSXTextLineTerminators {
+    fn visit_mut_jsx_text(&mut self, text: &mut JSXText) {
+        for value in [&mut text.value, &mut text.raw] {
+            *value = undouble_line_terminators(value).into();
+        }
+    }
+}
+
+/// Reads the doubled line terminators once each, blank lines are kept.
+fn undouble_line_terminators(text: &str) -> String {
+    let mut undoubled = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let (terminator, doubled) = match rest {
+            _ if rest.starts_with("\n\r\n") => ("\r\n", 3),
+            _ if rest.starts_with("\n\n") => ("\n", 2),
+            _ if rest.starts_with("\r\r") => ("\r", 2),
+            _ => {
+                undoubled.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        };
+
+        undoubled.push_str(terminator);
+        rest = &rest[doubled..];
+    }
+
+    undoubled
 }
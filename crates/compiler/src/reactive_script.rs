@@ -81,7 +81,7 @@ impl<'a> Parser<'a> {
         if let Some(arena) = self.context.arena.as_mut() {
             arena.alloc_with_id(|id| {
                 scope.id = Some(id);
-                return scope;
+                scope
             })
         } else {
             panic!("arena is None")
@@ -104,7 +104,7 @@ impl<'a> Parser<'a> {
 
     fn update_last_scope<F>(&mut self, update_fn: F)
     where
-        F: FnOnce(&mut Scope) -> (),
+        F: FnOnce(&mut Scope),
     {
         if let Some(parent) = self.context.scope_stack.last_mut() {
             update_fn(parent);
@@ -161,43 +161,38 @@ impl<'a> Visit for Parser<'a> {
     }
 
     fn visit_labeled_stmt(&mut self, n: &swc_ecma_ast::LabeledStmt) {
-        match &*n.label.sym {
-            "$" => {
-                if self.context.cur_reactive_stmt.is_some() {
-                    panic!("nested reactive statements is not supported")
-                }
+        if &*n.label.sym == "$" {
+            if self.context.cur_reactive_stmt.is_some() {
+                panic!("nested reactive statements is not supported")
+            }
 
-                match &*n.body {
-                    swc_ecma_ast::Stmt::Expr(e) => {
-                        self.context.cur_reactive_stmt = Some(ReactiveStatement::default());
-                        e.visit_with(self);
-                        let r_stmt = self
-                            .context
-                            .cur_reactive_stmt
-                            .take()
-                            .expect("reactive statement exists");
-                        self.register_reactive_statement(r_stmt);
-                    }
-                    _ => panic!("only expression statements are supported in reactive statement"),
+            match &*n.body {
+                swc_ecma_ast::Stmt::Expr(e) => {
+                    self.context.cur_reactive_stmt = Some(ReactiveStatement::default());
+                    e.visit_with(self);
+                    let r_stmt = self
+                        .context
+                        .cur_reactive_stmt
+                        .take()
+                        .expect("reactive statement exists");
+                    self.register_reactive_statement(r_stmt);
                 }
+                _ => panic!("only expression statements are supported in reactive statement"),
             }
-            _ => (),
         }
     }
 
     fn visit_var_declarator(&mut self, n: &swc_ecma_ast::VarDeclarator) {
-        match &n.name {
-            Pat::Ident(id) => self.register_var_decl(id.id.sym.clone()),
-            _ => (),
+        if let Pat::Ident(id) = &n.name {
+            self.register_var_decl(id.id.sym.clone());
         }
     }
 
     fn visit_call_expr(&mut self, n: &swc_ecma_ast::CallExpr) {
         if let Some(cur_reactive_stmt) = self.context.cur_reactive_stmt.as_mut() {
             for arg in &n.args {
-                match &*arg.expr {
-                    Expr::Ident(id) => cur_reactive_stmt.signals.push(id.sym.clone()),
-                    _ => (),
+                if let Expr::Ident(id) = &*arg.expr {
+                    cur_reactive_stmt.signals.push(id.sym.clone());
                 }
             }
         }
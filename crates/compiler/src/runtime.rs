@@ -0,0 +1,531 @@
+//! Helpers called by the generated render and client code. Only the helpers
+//! a file uses are emitted into its modules.
+
+use std::collections::BTreeSet;
+
+use swc_atoms::JsWord;
+use swc_common::{FileName, SourceMap, Span, DUMMY_SP};
+use swc_ecma_ast::{CallExpr, Callee, Expr, ExprOrSpread, Ident, ModuleItem};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Helper {
+    /// Converts a value to a string that is safe to place in HTML
+    Escape,
+    /// Renders a dynamic attribute, omitting it for `false`, `null` and `undefined`
+    Attr,
+    /// Renders a dynamic child, escaping strings and skipping `null`,
+    /// `undefined` and booleans
+    Child,
+    /// Renders trusted markup as is, see `innerHTML`
+    Raw,
+    /// Marks chunks as rendered markup so `$$child` does not escape them
+    Markup,
+    /// Renders the children or a named slot passed to a component
+    Slot,
+    /// Renders `{...attrs}`, skipping event handlers and `undefined` values
+    SpreadAttrs,
+    /// Joins the names of a class array or object
+    Class,
+    /// Converts a style object to CSS
+    Style,
+    /// Marks `<head>` content of a page, moved into the head of the document
+    Head,
+    /// Marks the children of the `<head>` or `<body>` of a document
+    Region,
+    /// Adds the doctype, the head content of the page and the scripts of
+    /// the document
+    Document,
+    /// Client runtime, state read with a getter and updated with a setter
+    UseState,
+    /// Client runtime, calls the handlers of the elements marked with
+    /// `data-bmr-<event>` from one listener per event
+    Delegate,
+    /// Client runtime, runs a computation again when the state it read is set
+    Effect,
+    /// Client runtime, renders a child again between its `<!--bmr:id-->` markers
+    BindText,
+    /// Client runtime, sets an attribute of the elements marked with
+    /// `data-bmr-hole`
+    BindAttr,
+    /// Creates a state of the client block on the server with its initial
+    /// value, recorded for `$$serialize`
+    ServerState,
+    /// Renders the initial state and server values of the client module as
+    /// JSON that is safe to inline in HTML
+    Serialize,
+    /// Client runtime, reads a value from the JSON rendered by `$$serialize`
+    Resume,
+}
+
+impl Helper {
+    pub fn name(self) -> &'static str {
+        match self {
+            Helper::Escape => "$$escape",
+            Helper::Attr => "$$attr",
+            Helper::Child => "$$child",
+            Helper::Raw => "$$raw",
+            Helper::Markup => "$$markup",
+            Helper::Slot => "$$slot",
+            Helper::SpreadAttrs => "$$spreadAttrs",
+            Helper::Class => "$$class",
+            Helper::Style => "$$style",
+            Helper::Head => "$$head",
+            Helper::Region => "$$region",
+            Helper::Document => "$$document",
+            Helper::UseState => "$$useState",
+            Helper::Delegate => "$$delegate",
+            Helper::Effect => "$$effect",
+            Helper::BindText => "$$bindText",
+            Helper::BindAttr => "$$bindAttr",
+            Helper::ServerState => "$$serverState",
+            Helper::Serialize => "$$serialize",
+            Helper::Resume => "$$resume",
+        }
+    }
+
+    const ALL: [Helper; 20] = [
+        Helper::Escape,
+        Helper::Attr,
+        Helper::Child,
+        Helper::Raw,
+        Helper::Markup,
+        Helper::Slot,
+        Helper::SpreadAttrs,
+        Helper::Class,
+        Helper::Style,
+        Helper::Head,
+        Helper::Region,
+        Helper::Document,
+        Helper::UseState,
+        Helper::Delegate,
+        Helper::Effect,
+        Helper::BindText,
+        Helper::BindAttr,
+        Helper::ServerState,
+        Helper::Serialize,
+        Helper::Resume,
+    ];
+
+    fn from_name(name: &str) -> Option<Helper> {
+        Helper::ALL.into_iter().find(|helper| helper.name() == name)
+    }
+
+    fn dependencies(self) -> &'static [Helper] {
+        match self {
+            Helper::Escape => &[],
+            Helper::Attr | Helper::Child => &[Helper::Escape],
+            Helper::Raw | Helper::Markup => &[],
+            Helper::Slot => &[Helper::Child],
+            Helper::SpreadAttrs => &[Helper::Attr, Helper::Class, Helper::Style],
+            Helper::Class | Helper::Style => &[],
+            Helper::Head | Helper::Region | Helper::Document => &[],
+            Helper::UseState | Helper::Delegate => &[],
+            Helper::Effect => &[Helper::UseState],
+            Helper::BindText | Helper::BindAttr => &[Helper::Effect],
+            Helper::ServerState | Helper::Serialize | Helper::Resume => &[],
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Helper::Escape => {
+                r#"
+function $$escape(value) {
+  return String(value).replace(
+    /[&<>"']/g,
+    (c) => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;", "'": "&#39;" })[c]
+  );
+}"#
+            }
+            Helper::Attr => {
+                r#"
+function $$attr(name, value) {
+  if (value == null || value === false || typeof value === "function") return "";
+  if (value === true) return " " + name;
+  return " " + name + '="' + $$escape(value) + '"';
+}"#
+            }
+            Helper::Child => {
+                r#"
+function $$child(value) {
+  if (value == null || typeof value === "boolean") return "";
+  if (Array.isArray(value)) {
+    return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+  }
+  return $$escape(value);
+}"#
+            }
+            Helper::Raw => {
+                r#"
+function $$raw(value) {
+  return value == null || value === false ? "" : String(value);
+}"#
+            }
+            Helper::Markup => {
+                r#"
+function $$markup(chunks) {
+  chunks[Symbol.for("boomer.markup")] = true;
+  return chunks;
+}"#
+            }
+            Helper::Slot => {
+                r#"
+function $$slot(props, name, fallback) {
+  const content = name == null ? props.children : props.slots && props.slots[name];
+  return $$child(content == null ? fallback : content);
+}"#
+            }
+            Helper::SpreadAttrs => {
+                r#"
+function $$spreadAttrs(attrs) {
+  if (attrs == null) return "";
+  const merged = new Map();
+  for (const key of Object.keys(attrs)) {
+    const value = attrs[key];
+    if (key === "children" || /^on/i.test(key)) continue;
+    if (!/^[^\s"'<>\/=]+$/.test(key)) continue;
+    const name = key === "className" ? "class" : key === "htmlFor" ? "for" : key;
+    // the last value wins, `undefined` removes the attribute
+    merged.delete(name);
+    if (value === undefined) continue;
+    merged.set(name, name === "class" ? $$class(value) : name === "style" ? $$style(value) : value);
+  }
+  let html = "";
+  for (const [name, value] of merged) html += $$attr(name, value);
+  return html;
+}"#
+            }
+            Helper::Class => {
+                r#"
+function $$class(value) {
+  let classes;
+  if (Array.isArray(value)) {
+    classes = value.map($$class).filter(Boolean).join(" ");
+  } else if (value != null && typeof value === "object") {
+    classes = Object.keys(value).filter((key) => value[key]).join(" ");
+  } else {
+    classes = value == null || typeof value === "boolean" ? "" : String(value);
+  }
+  return classes === "" ? null : classes;
+}"#
+            }
+            Helper::Style => {
+                r#"
+const $$unitless = new Set(["animationIterationCount", "aspectRatio", "borderImageOutset", "borderImageSlice", "borderImageWidth", "boxFlex", "boxFlexGroup", "boxOrdinalGroup", "columnCount", "columns", "fillOpacity", "flex", "flexGrow", "flexNegative", "flexOrder", "flexPositive", "flexShrink", "floodOpacity", "fontWeight", "gridArea", "gridColumn", "gridColumnEnd", "gridColumnStart", "gridRow", "gridRowEnd", "gridRowStart", "lineClamp", "lineHeight", "opacity", "order", "orphans", "scale", "stopOpacity", "strokeDasharray", "strokeDashoffset", "strokeMiterlimit", "strokeOpacity", "strokeWidth", "tabSize", "widows", "zIndex", "zoom"]);
+function $$style(value) {
+  if (value == null || typeof value === "boolean") return null;
+  if (typeof value !== "object") return String(value);
+  const declarations = [];
+  for (const key of Object.keys(value)) {
+    const v = value[key];
+    if (v == null || typeof v === "boolean" || String(v).trim() === "") continue;
+    const property = key.startsWith("--")
+      ? key
+      : key.replace(/^ms(?=[A-Z])/, "-ms").replace(/[A-Z]/g, (c) => "-" + c.toLowerCase());
+    const unitless = $$unitless.has(key.replace(/^(?:Webkit|Moz|ms|O)([A-Z])/, (_, c) => c.toLowerCase()));
+    const css = typeof v === "number" && v !== 0 && !unitless ? v + "px" : String(v).trim();
+    declarations.push(property + ":" + css);
+  }
+  return declarations.length === 0 ? null : declarations.join(";");
+}"#
+            }
+            Helper::Head => {
+                r#"
+function $$head(chunks) {
+  chunks[Symbol.for("boomer.head")] = true;
+  return chunks;
+}"#
+            }
+            Helper::Region => {
+                r#"
+function $$region(name, chunks) {
+  chunks[Symbol.for("boomer.region")] = name;
+  return chunks;
+}"#
+            }
+            Helper::Document => {
+                r#"
+function $$document(chunks, bodyEnd) {
+  const head = [];
+  const regions = {};
+  const copy = (value) => {
+    if (!Array.isArray(value)) return value;
+    if (value[Symbol.for("boomer.head")]) {
+      head.push(value.map(copy));
+      return "";
+    }
+    const chunks = value.map(copy);
+    const region = value[Symbol.for("boomer.region")];
+    if (region) regions[region] = chunks;
+    return chunks;
+  };
+  const html = copy(chunks);
+  (regions.head || html).unshift(...head);
+  (regions.body || html).push(...bodyEnd);
+  return ["<!DOCTYPE html>", html];
+}"#
+            }
+            Helper::UseState => {
+                r#"
+let $$observer = null;
+function $$useState(value) {
+  const observers = new Set();
+  const get = () => {
+    if ($$observer) observers.add($$observer);
+    return value;
+  };
+  const set = (next) => {
+    next = typeof next === "function" ? next(value) : next;
+    if (Object.is(next, value)) return;
+    value = next;
+    for (const observer of [...observers]) observer();
+  };
+  return [get, set];
+}"#
+            }
+            Helper::Delegate => {
+                r#"
+const $$captured = new Set(["blur", "error", "focus", "load", "mouseenter", "mouseleave", "scroll"]);
+function $$delegate(module, events, handlers) {
+  const prefix = module + ":";
+  for (const event of events) {
+    const marker = "data-bmr-" + event;
+    // events that do not bubble are only handled on their target
+    const captured = $$captured.has(event);
+    document.addEventListener(event, (e) => {
+      for (let node = e.target; node && node.nodeType === 1; node = node.parentNode) {
+        const id = node.getAttribute(marker);
+        // the markers of other modules are handled by their own listeners
+        if (id !== null && id.startsWith(prefix)) {
+          handlers[id.slice(prefix.length)].call(node, e);
+          if (e.cancelBubble) return;
+        }
+        if (captured) return;
+      }
+    }, captured);
+  }
+}"#
+            }
+            Helper::Effect => {
+                r#"
+function $$effect(compute, apply) {
+  let rendered = false;
+  const run = () => {
+    const observer = $$observer;
+    $$observer = run;
+    let value;
+    try {
+      value = compute();
+    } finally {
+      $$observer = observer;
+    }
+    // the server rendered the first value
+    if (rendered) apply(value);
+    rendered = true;
+  };
+  run();
+}"#
+            }
+            Helper::BindText => {
+                r#"
+let $$holes;
+function $$bindText(id, value) {
+  if (!$$holes) {
+    $$holes = {};
+    const walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);
+    while (walker.nextNode()) {
+      const match = /^bmr:(.+)$/.exec(walker.currentNode.data);
+      if (match) ($$holes[match[1]] ||= []).push(walker.currentNode);
+    }
+  }
+  const starts = $$holes[id] || [];
+  $$effect(value, (chunks) => {
+    const html = [chunks].flat(Infinity).join("");
+    for (const start of starts) {
+      while (start.nextSibling && start.nextSibling.data !== "/bmr:" + id) {
+        start.nextSibling.remove();
+      }
+      const template = document.createElement("template");
+      template.innerHTML = html;
+      start.after(template.content);
+    }
+  });
+}"#
+            }
+            Helper::ServerState => {
+                r#"
+function $$serverState(states, index, initial) {
+  let value;
+  try {
+    value = initial();
+    states[index] = value;
+  } catch (error) {
+    // browser only globals, the client computes the initial value instead
+    if (!(error instanceof ReferenceError)) throw error;
+  }
+  return [() => value, () => {}];
+}"#
+            }
+            Helper::Serialize => {
+                r#"
+function $$serialize(module, states, values) {
+  // JSON.stringify drops or degrades what it cannot represent, the client
+  // would resume a different value than the server rendered
+  const check = (value, path) => {
+    const type = typeof value;
+    if (value === null || type === "string" || type === "boolean") return;
+    if (type === "number" && Number.isFinite(value)) return;
+    if (Array.isArray(value)) {
+      value.forEach((item, index) => check(item, path + "[" + index + "]"));
+      return;
+    }
+    const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+    if (proto === Object.prototype || proto === null) {
+      for (const key of Object.keys(value)) {
+        // missing properties read the same as undefined ones
+        if (value[key] !== undefined) check(value[key], path + "." + key);
+      }
+      return;
+    }
+    const kind =
+      type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+    throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+  };
+  for (const index of Object.keys(states)) {
+    if (states[index] !== undefined) check(states[index], "useState #" + index);
+  }
+  for (const name of Object.keys(values)) {
+    if (values[name] !== undefined) check(values[name], name);
+  }
+  const json = JSON.stringify({ state: states, values }).replace(
+    /[<>&\u2028\u2029]/g,
+    (c) => "\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+  );
+  return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}"#
+            }
+            Helper::Resume => {
+                r#"
+let $$payload;
+function $$resume(module, kind, key, initial) {
+  if ($$payload === undefined) {
+    const script = document.getElementById("bmr-payload:" + module);
+    $$payload = script ? JSON.parse(script.textContent) : {};
+  }
+  const values = $$payload[kind];
+  if (values && key in values) return values[key];
+  return initial ? initial() : undefined;
+}"#
+            }
+            Helper::BindAttr => {
+                r#"
+function $$bindAttr(id, name, value) {
+  const elements = document.querySelectorAll('[data-bmr-hole="' + id + '"]');
+  $$effect(value, (v) => {
+    const removed = v == null || v === false || typeof v === "function";
+    for (const element of elements) {
+      if (removed) element.removeAttribute(name);
+      else element.setAttribute(name, v === true ? "" : String(v));
+      // attributes only set the initial value of form fields
+      if (name === "value") element.value = removed ? "" : String(v);
+      if (name === "checked") element.checked = !removed;
+    }
+  });
+}"#
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Runtime {
+    used: BTreeSet<Helper>,
+}
+
+impl Runtime {
+    /// Builds a call to `helper`, marking it as used.
+    pub fn call(&mut self, helper: Helper, span: Span, args: Vec<Expr>) -> Expr {
+        self.use_helper(helper);
+
+        Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+                JsWord::from(helper.name()),
+                DUMMY_SP,
+            )))),
+            args: args
+                .into_iter()
+                .map(|expr| ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(expr),
+                })
+                .collect(),
+            type_args: None,
+        })
+    }
+
+    /// Marks the helpers called in `expr` as used, for code that was
+    /// compiled with another runtime.
+    pub fn track(&mut self, expr: &Expr) {
+        expr.visit_with(&mut TrackHelpers { runtime: self });
+    }
+
+    fn use_helper(&mut self, helper: Helper) {
+        if self.used.insert(helper) {
+            for dependency in helper.dependencies() {
+                self.use_helper(*dependency);
+            }
+        }
+    }
+
+    /// The declarations of the used helpers.
+    pub fn emit(&self) -> Vec<ModuleItem> {
+        if self.used.is_empty() {
+            return vec![];
+        }
+
+        let source: String = self.used.iter().map(|helper| helper.source()).collect();
+
+        // the helpers are parsed from their own source map, their spans
+        // mean nothing in the file being compiled
+        let source_map = SourceMap::default();
+        let source_file = source_map.new_source_file(FileName::Anon, source);
+
+        let lexer = Lexer::new(
+            Syntax::Es(Default::default()),
+            Default::default(),
+            StringInput::from(&*source_file),
+            None,
+        );
+
+        let mut module = Parser::new_from(lexer)
+            .parse_module()
+            .expect("runtime helpers are valid JavaScript");
+
+        module.visit_mut_with(&mut DropSpans);
+
+        module.body
+    }
+}
+
+struct TrackHelpers<'a> {
+    runtime: &'a mut Runtime,
+}
+
+impl<'a> Visit for TrackHelpers<'a> {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if let Some(helper) = Helper::from_name(&ident.sym) {
+            self.runtime.use_helper(helper);
+        }
+    }
+}
+
+struct DropSpans;
+
+impl VisitMut for DropSpans {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+}
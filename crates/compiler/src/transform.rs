@@ -1,324 +1,3002 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet},
+};
 
 use swc_atoms::JsWord;
-use swc_common::{Spanned, DUMMY_SP};
+use swc_common::{Span, Spanned, DUMMY_SP};
 use swc_ecma_ast::{
-    ArrayLit, BlockStmt, CallExpr, Decl, ExportDecl, Expr, ExprOrSpread, FnDecl, Function, Ident,
-    JSXAttr, JSXElement, JSXElementChild, JSXElementName, JSXExpr, JSXExprContainer, JSXText, Lit,
-    Module, ModuleDecl, ModuleItem, ReturnStmt, Stmt, Str,
+    ArrayLit, ArrowExpr, AssignPat, AssignPatProp, BindingIdent, BlockStmt, BlockStmtOrExpr, Bool,
+    BreakStmt, CallExpr, Callee, CatchClause, ClassDecl, ClassExpr, ContinueStmt, Decl, ExportDecl,
+    ExportSpecifier, Expr, ExprOrSpread, ExprStmt, FnDecl, FnExpr, Function, Ident,
+    ImportDefaultSpecifier, ImportNamedSpecifier, ImportSpecifier, Invalid, JSXAttr, JSXAttrName,
+    JSXAttrOrSpread, JSXAttrValue, JSXElement, JSXElementChild, JSXElementName, JSXExpr,
+    JSXExprContainer, JSXFragment, JSXMemberExpr, JSXObject, JSXOpeningElement, JSXSpreadChild,
+    JSXText, KeyValueProp, LabeledStmt, Lit, MemberExpr, MemberProp, Module, ModuleDecl,
+    ModuleExportName, ModuleItem, NamedExport, Null, Number, ObjectLit, Param, Pat, PrivateName,
+    Prop, PropName, PropOrSpread, ReturnStmt, SpreadElement, Stmt, Str, SuperProp, VarDecl,
+    VarDeclKind, VarDeclarator,
+};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use crate::{
+    parser::{
+        is_use_state_call, ClientBlock, JSXRoot, ParseResult, ParserError, ServerBlock, SyntaxError,
+    },
+    runtime::{Helper, Runtime},
 };
-use swc_ecma_visit::{Visit, VisitWith};
 
-use crate::parser::{ClientBlock, ParseResult, ServerBlock};
+pub struct TransformOutput {
+    /// The server render module
+    pub server: Module,
+    /// The code of the browser module, when the file has client code
+    pub client_code: Option<String>,
+    pub errors: Vec<ParserError>,
+}
 
 pub struct BmrTransform;
 
 impl BmrTransform {
-    pub fn transform(parse_result: ParseResult) -> Module {
-        let mut jsx_transform = JSXTransform::new(&parse_result.client);
-        let markup = jsx_transform.transform(parse_result.jsx);
-        let server_stmts = if let Some(server_block) = parse_result.server {
-            ServerTransform::transform(server_block)
+    /// `emit_client` generates the code of the browser module. Documents
+    /// load it from `client_url`, or inline it when there is no url. The
+    /// markers and payload of the page are namespaced by `module`, which is
+    /// unique to the file.
+    pub fn transform(
+        parse_result: &ParseResult,
+        module: &str,
+        client_url: Option<&str>,
+        emit_client: impl FnOnce(&Module) -> String,
+    ) -> TransformOutput {
+        let mut declarations = ModuleDeclarations::split(parse_result);
+
+        let mut jsx_transform = JSXTransform::new(
+            &parse_result.client,
+            module,
+            declarations.components.clone(),
+            declarations.namespaces.clone(),
+        );
+        let root = jsx_transform.render_root(&parse_result.jsx);
+        let server_stmts = if let Some(server_block) = &parse_result.server {
+            ServerTransform::transform(server_block, &mut jsx_transform)
         } else {
             vec![]
         };
 
+        // the handlers and holes of the page and the components it declares
+        let bindings = jsx_transform.take_bindings();
+
+        // the markup reads the initial state on the server, the page
+        // serializes it for the client to resume from
+        let state_stmts = match &parse_result.client {
+            Some(client_block) => {
+                ServerState::transform(parse_result, client_block, &mut jsx_transform)
+            }
+            None => vec![],
+        };
+        check_client_declarations(
+            parse_result,
+            &server_stmts,
+            &state_stmts,
+            &root,
+            &mut jsx_transform,
+        );
+        let values = resumed_values(parse_result, &bindings);
+        check_server_declarations(parse_result, &bindings, &mut jsx_transform);
+        check_server_scopes(parse_result, bindings.exprs(), &mut jsx_transform);
+
+        // the imports each module reads, handlers only run on the client and
+        // holes on both
+        let mut server_idents = IdentCollector::default();
+        server_stmts.visit_with(&mut server_idents);
+        state_stmts.visit_with(&mut server_idents);
+        root.chunks.visit_with(&mut server_idents);
+        for decl in &declarations.exports {
+            decl.visit_with(&mut server_idents);
+        }
+
+        let mut client_idents = IdentCollector::default();
+        if let Some(client) = &parse_result.client {
+            client.block.visit_with(&mut client_idents);
+        }
+        for expr in bindings.exprs() {
+            expr.visit_with(&mut client_idents);
+        }
+
+        declarations.route_imports(&server_idents, &client_idents);
+
+        let payload = if !state_stmts.is_empty() || !values.is_empty() {
+            Some(Self::payload(
+                module,
+                !state_stmts.is_empty(),
+                &values,
+                &mut jsx_transform.runtime,
+            ))
+        } else {
+            None
+        };
+
+        let client = if parse_result.client.is_some()
+            || !declarations.client_imports.is_empty()
+            || !bindings.is_empty()
+        {
+            Some(ClientTransform::transform(
+                &parse_result.client,
+                module,
+                declarations.client_imports.clone(),
+                bindings,
+                values,
+                payload.is_some(),
+            ))
+        } else {
+            None
+        };
+        let client_code = client.as_ref().map(emit_client);
+
+        let client_script = client_code.as_deref().map(|code| match client_url {
+            Some(url) => ClientScript::Src(url),
+            None => ClientScript::Inline(code),
+        });
+        let markup = jsx_transform.render_function(root, client_script, payload, state_stmts);
+
         let mut module_items: Vec<ModuleItem> = vec![];
 
+        for import in declarations.server_imports {
+            module_items.push(ModuleItem::ModuleDecl(import));
+        }
+
+        module_items.extend(jsx_transform.runtime.emit());
+
         for stmt in server_stmts {
             module_items.push(ModuleItem::Stmt(stmt));
         }
 
+        for decl in declarations.exports {
+            module_items.push(ModuleItem::ModuleDecl(decl));
+        }
+
         for decl in markup {
             module_items.push(ModuleItem::ModuleDecl(decl));
         }
 
-        Module {
-            shebang: None,
-            span: DUMMY_SP,
-            body: module_items,
+        TransformOutput {
+            server: Module {
+                shebang: None,
+                span: DUMMY_SP,
+                body: module_items,
+            },
+            client_code,
+            errors: jsx_transform.take_errors(),
         }
     }
-}
 
-macro_rules! html_open_tag {
-    ($tag:expr) => {
-        str_lit!(
-            JsWord::from(format!(r#""<{}>""#, $tag)),
-            JsWord::from(format!("<{}>", $tag))
+    /// `$$serialize(module, $$states, { value })`, the initial state and the
+    /// server values of the page's client module.
+    fn payload(module: &str, has_state: bool, values: &[JsWord], runtime: &mut Runtime) -> Expr {
+        let states = if has_state {
+            Expr::Ident(Ident::new(JsWord::from("$$states"), DUMMY_SP))
+        } else {
+            Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            })
+        };
+
+        let values = Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: values
+                .iter()
+                .map(|value| {
+                    PropOrSpread::Prop(Box::new(Prop::Shorthand(Ident::new(
+                        value.clone(),
+                        DUMMY_SP,
+                    ))))
+                })
+                .collect(),
+        });
+
+        runtime.call(
+            Helper::Serialize,
+            DUMMY_SP,
+            vec![
+                Expr::Lit(Lit::Str(Str::from(JsWord::from(module)))),
+                states,
+                values,
+            ],
         )
+    }
+}
+
+/// The top level values of the server block that the client module uses,
+/// its block, handlers or holes. They are resumed from the payload of the
+/// page as the client cannot run the server block.
+fn resumed_values(parse_result: &ParseResult, bindings: &ClientBindings) -> Vec<JsWord> {
+    let server = match &parse_result.server {
+        Some(server) => server,
+        None => return vec![],
     };
+
+    let mut used = IdentCollector::default();
+    let mut client_names = vec![];
+
+    if let Some(client) = &parse_result.client {
+        client.block.visit_with(&mut used);
+        client_names = declared_names(&client.block.stmts);
+    }
+
+    for expr in bindings.exprs() {
+        expr.visit_with(&mut used);
+    }
+
+    let mut server_names = BindingNames::default();
+    for stmt in &server.block.stmts {
+        if let Stmt::Decl(Decl::Var(var)) = stmt {
+            var.decls.visit_with(&mut server_names);
+        }
+    }
+
+    server_names
+        .names
+        .into_iter()
+        .filter(|name| used.contains(name) && !client_names.contains(name))
+        .collect()
 }
 
-macro_rules! html_close_tag {
-    ($tag:expr) => {
-        str_lit!(
-            JsWord::from(format!(r#""</{}>""#, $tag)),
-            JsWord::from(format!("</{}>", $tag))
-        )
+/// Reports the names of the client block the server module reads, other than
+/// the state `render` creates, `double` in
+/// `const double = () => count() * 2` read by `<p>{double()}</p>`. The
+/// client block does not run on the server.
+fn check_client_declarations(
+    parse_result: &ParseResult,
+    server_stmts: &[Stmt],
+    state_stmts: &[Stmt],
+    root: &RenderedRoot,
+    jsx_transform: &mut JSXTransform,
+) {
+    let client_names = match &parse_result.client {
+        Some(client) => declared_names(&client.block.stmts),
+        None => return,
     };
+
+    let mut reads = FreeIdents::default();
+    reads.scopes.push(declared_names(server_stmts));
+    reads.scopes.push(declared_names(state_stmts));
+    for stmt in server_stmts {
+        stmt.visit_with(&mut reads);
+    }
+    for chunk in root.chunks.iter().flatten() {
+        chunk.visit_with(&mut reads);
+    }
+
+    for ident in reads.idents {
+        if client_names.contains(&ident.sym) {
+            jsx_transform.emit_error(ident.span, SyntaxError::ClientOnlyBinding(ident.sym));
+        }
+    }
 }
 
-macro_rules! str_lit {
-    ($val:expr, $raw:expr) => {
-        Some(ExprOrSpread {
-            spread: None,
-            expr: Box::new(Expr::Lit(Lit::Str(Str {
-                span: DUMMY_SP,
-                raw: Some($val),
-                value: $raw,
-            }))),
-        })
+/// Reports the functions and classes of the server block that client code,
+/// its block, handlers or holes, uses. Only values are resumed from the
+/// payload of the page.
+fn check_server_declarations(
+    parse_result: &ParseResult,
+    bindings: &ClientBindings,
+    jsx_transform: &mut JSXTransform,
+) {
+    let declarations: Vec<JsWord> = match &parse_result.server {
+        Some(server) => server
+            .block
+            .stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Decl(Decl::Fn(fn_decl)) => Some(fn_decl.ident.sym.clone()),
+                Stmt::Decl(Decl::Class(class_decl)) => Some(class_decl.ident.sym.clone()),
+                _ => None,
+            })
+            .collect(),
+        None => return,
     };
+    if declarations.is_empty() {
+        return;
+    }
+
+    let mut reads = FreeIdents::default();
+    if let Some(client) = &parse_result.client {
+        reads.scopes.push(declared_names(&client.block.stmts));
+        client.block.visit_with(&mut reads);
+    }
+    for expr in bindings.exprs() {
+        expr.visit_with(&mut reads);
+    }
+
+    for ident in reads.idents {
+        if declarations.contains(&ident.sym) {
+            jsx_transform.emit_error(ident.span, SyntaxError::UnresumableServerBinding(ident.sym));
+        }
+    }
 }
 
-macro_rules! array_lit {
-    ($elems:expr) => {
-        Some(ExprOrSpread {
-            spread: None,
-            expr: Box::new(Expr::Array(ArrayLit {
-                span: DUMMY_SP,
-                elems: $elems,
-            })),
+/// Reports the names the handlers and holes of the page read from the markup
+/// or the functions of the server block, `item` in
+/// `items.map((item) => <button onClick={() => alert(item)} />)`. The client
+/// module only has the top level names of both blocks.
+fn check_server_scopes<'e>(
+    parse_result: &ParseResult,
+    exprs: impl Iterator<Item = &'e Expr>,
+    jsx_transform: &mut JSXTransform,
+) {
+    let mut scoped = ScopedNames::default();
+    match &parse_result.jsx {
+        Some(JSXRoot::Element(element)) => element.visit_with(&mut scoped),
+        Some(JSXRoot::Fragment(fragment)) => fragment.visit_with(&mut scoped),
+        None => (),
+    }
+    if let Some(server) = &parse_result.server {
+        server.block.visit_with(&mut scoped);
+        // shadowed somewhere, the top level names are the ones resumed
+        for name in declared_names(&server.block.stmts) {
+            scoped.names.remove(&name);
+        }
+    }
+    // the props of the page and its components
+    scoped.names.insert(JsWord::from("props"));
+
+    let mut reads = FreeIdents::default();
+    if let Some(client) = &parse_result.client {
+        reads.scopes.push(declared_names(&client.block.stmts));
+    }
+    reads.scopes.push(import_names(parse_result));
+    for expr in exprs {
+        expr.visit_with(&mut reads);
+    }
+
+    for ident in reads.idents {
+        if scoped.names.contains(&ident.sym) {
+            jsx_transform.emit_error(ident.span, SyntaxError::ServerScopedBinding(ident.sym));
+        }
+    }
+}
+
+/// The local names of the imports of a Boomer file.
+fn import_names(parse_result: &ParseResult) -> Vec<JsWord> {
+    parse_result
+        .declarations
+        .iter()
+        .filter_map(|decl| match decl {
+            ModuleDecl::Import(import) => Some(import),
+            _ => None,
         })
-    };
+        .flat_map(|import| &import.specifiers)
+        .map(|specifier| match specifier {
+            ImportSpecifier::Named(named) => named.local.sym.clone(),
+            ImportSpecifier::Default(default) => default.local.sym.clone(),
+            ImportSpecifier::Namespace(namespace) => namespace.local.sym.clone(),
+        })
+        .collect()
 }
 
-#[derive(Default, Clone, Copy)]
-pub struct JSXTransformContext {
-    // <button onClick={...
-    in_attribute_expr: bool,
-    // <div>{...
-    in_child_expr: bool,
+/// Every name declared in some code, at any depth.
+#[derive(Default)]
+struct ScopedNames {
+    names: HashSet<JsWord>,
 }
 
-pub struct JSXTransform<'a> {
-    cur_children: Vec<Option<ExprOrSpread>>,
+impl Visit for ScopedNames {
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.names.insert(binding.id.sym.clone());
+    }
 
-    ctx: JSXTransformContext,
+    fn visit_assign_pat_prop(&mut self, prop: &AssignPatProp) {
+        self.names.insert(prop.key.sym.clone());
+        prop.visit_children_with(self);
+    }
 
-    client_block: &'a Option<ClientBlock>,
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.names.insert(fn_decl.ident.sym.clone());
+        fn_decl.visit_children_with(self);
+    }
 
-    jsx_el_stack: Vec<Vec<Option<ExprOrSpread>>>,
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        self.names
+            .extend(fn_expr.ident.iter().map(|id| id.sym.clone()));
+        fn_expr.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.names.insert(class_decl.ident.sym.clone());
+        class_decl.visit_children_with(self);
+    }
 }
 
-impl<'a> JSXTransform<'a> {
-    pub fn new(client_block: &'a Option<ClientBlock>) -> Self {
-        Self {
-            cur_children: Default::default(),
-            ctx: Default::default(),
-            client_block,
-            jsx_el_stack: Default::default(),
+/// The top level imports and exports of a Boomer file, the imports split
+/// between the server and client modules by which one uses them.
+#[derive(Default)]
+struct ModuleDeclarations {
+    imports: Vec<ModuleDecl>,
+    server_imports: Vec<ModuleDecl>,
+    client_imports: Vec<ModuleDecl>,
+    exports: Vec<ModuleDecl>,
+    /// Imported and declared names that can be used as components
+    components: HashSet<JsWord>,
+    /// Imported and declared names that can hold components, `Icons` in
+    /// `<Icons.Star />`
+    namespaces: HashSet<JsWord>,
+}
+
+impl ModuleDeclarations {
+    fn split(parse_result: &ParseResult) -> Self {
+        let mut declarations = ModuleDeclarations::default();
+
+        if let Some(server) = &parse_result.server {
+            declarations
+                .components
+                .extend(server.function_declarations.keys().cloned());
+
+            for stmt in &server.block.stmts {
+                if let Stmt::Decl(Decl::Var(var)) = stmt {
+                    for declarator in &var.decls {
+                        let binding = match &declarator.name {
+                            Pat::Ident(binding) => binding.id.sym.clone(),
+                            _ => continue,
+                        };
+
+                        // `const Card = ({ title }) => ...`
+                        if matches!(
+                            declarator.init.as_deref(),
+                            Some(Expr::Arrow(_) | Expr::Fn(_))
+                        ) {
+                            declarations.components.insert(binding.clone());
+                        }
+
+                        // `const Icons = { ... }` used as `<Icons.Star />`
+                        declarations.namespaces.insert(binding);
+                    }
+                }
+            }
         }
-    }
-    pub fn transform(&mut self, jsx: JSXElement) -> Vec<ModuleDecl> {
-        self.visit_jsx_element(&jsx);
 
-        let arr = ArrayLit {
-            span: DUMMY_SP,
-            elems: self.cur_children.drain(..).collect(),
-        };
+        for decl in &parse_result.declarations {
+            let mut decl = decl.clone();
+            rewrite_source(&mut decl);
+            if !erase_types(&mut decl) {
+                continue;
+            }
+            let decl = &decl;
 
-        let return_stmt = Stmt::Return(ReturnStmt {
-            span: DUMMY_SP,
-            arg: Some(Box::new(Expr::Array(arr))),
-        });
+            match decl {
+                ModuleDecl::Import(import) => {
+                    let locals: Vec<&JsWord> = import
+                        .specifiers
+                        .iter()
+                        .map(|specifier| match specifier {
+                            ImportSpecifier::Named(named) => &named.local.sym,
+                            ImportSpecifier::Default(default) => &default.local.sym,
+                            ImportSpecifier::Namespace(namespace) => &namespace.local.sym,
+                        })
+                        .collect();
 
-        let render_func = Decl::Fn(FnDecl {
-            ident: Ident {
-                span: DUMMY_SP,
-                sym: JsWord::from("render"),
-                optional: false,
-            },
-            declare: false,
-            function: Function {
-                params: vec![],
-                decorators: vec![],
-                span: DUMMY_SP,
-                is_generator: false,
-                is_async: false,
-                type_params: None,
-                return_type: None,
-                body: Some(BlockStmt {
-                    span: DUMMY_SP,
-                    stmts: vec![return_stmt],
-                }),
-            },
-        });
+                    for specifier in &import.specifiers {
+                        match specifier {
+                            ImportSpecifier::Named(ImportNamedSpecifier { local, .. })
+                            | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. }) => {
+                                declarations.components.insert(local.sym.clone());
+                            }
+                            ImportSpecifier::Namespace(_) => (),
+                        }
+                    }
+                    declarations
+                        .namespaces
+                        .extend(locals.iter().map(|local| (*local).clone()));
 
-        vec![ModuleDecl::ExportDecl(ExportDecl {
-            span: DUMMY_SP,
-            decl: render_func,
-        })]
-    }
+                    declarations.imports.push(decl.clone());
+                }
+                _ => {
+                    if let ModuleDecl::ExportDecl(ExportDecl {
+                        decl: Decl::Fn(fn_decl),
+                        ..
+                    }) = decl
+                    {
+                        declarations.components.insert(fn_decl.ident.sym.clone());
+                    }
 
-    fn is_get_state(&self, id: JsWord) -> bool {
-        self.client_block
-            .as_ref()
-            .map_or(false, |block| block.use_state.get.contains(&id))
+                    declarations.exports.push(decl.clone());
+                }
+            }
+        }
+
+        declarations
     }
 
-    fn is_set_state(&self, id: JsWord) -> bool {
-        self.client_block
-            .as_ref()
-            .map_or(false, |block| block.use_state.set.contains(&id))
+    /// Splits the imports by the names the server and client modules read.
+    fn route_imports(&mut self, server_idents: &IdentCollector, client_idents: &IdentCollector) {
+        for decl in self.imports.drain(..) {
+            let locals: Vec<&JsWord> = match &decl {
+                ModuleDecl::Import(import) => import
+                    .specifiers
+                    .iter()
+                    .map(|specifier| match specifier {
+                        ImportSpecifier::Named(named) => &named.local.sym,
+                        ImportSpecifier::Default(default) => &default.local.sym,
+                        ImportSpecifier::Namespace(namespace) => &namespace.local.sym,
+                    })
+                    .collect(),
+                _ => continue,
+            };
+
+            let in_client = locals.iter().any(|local| client_idents.contains(local));
+            // side effect and unused imports stay on the server
+            let in_server = locals.is_empty()
+                || !in_client
+                || locals.iter().any(|local| server_idents.contains(local));
+
+            if in_client {
+                self.client_imports.push(decl.clone());
+            }
+
+            if in_server {
+                self.server_imports.push(decl);
+            }
+        }
     }
+}
 
-    fn with_ctx(&'a mut self, ctx: JSXTransformContext) -> WithContext<'a> {
-        let orig_ctx = self.ctx;
-        self.set_ctx(ctx);
+/// Erases the types imported or exported by `decl`, `false` when only types
+/// are, e.g. `import type { Foo } from "./types"` or
+/// `import { type Foo } from "./types"`.
+fn erase_types(decl: &mut ModuleDecl) -> bool {
+    match decl {
+        ModuleDecl::Import(import) => {
+            if import.type_only {
+                return false;
+            }
+
+            let side_effect = import.specifiers.is_empty();
+            import.specifiers.retain(|specifier| {
+                !matches!(specifier, ImportSpecifier::Named(named) if named.is_type_only)
+            });
+
+            side_effect || !import.specifiers.is_empty()
+        }
+        ModuleDecl::ExportNamed(export) => {
+            if export.type_only {
+                return false;
+            }
 
-        WithContext {
-            inner: self,
-            orig_ctx,
+            let empty = export.specifiers.is_empty();
+            export.specifiers.retain(|specifier| {
+                !matches!(specifier, ExportSpecifier::Named(named) if named.is_type_only)
+            });
+
+            empty || !export.specifiers.is_empty()
         }
+        _ => true,
     }
+}
 
-    fn set_ctx(&mut self, ctx: JSXTransformContext) {
-        self.ctx = ctx;
+/// Points imports of Boomer files at their compiled module,
+/// `import Header from "./header.bmr"` imports the `render` function of
+/// `./header.js`, `import { render as Header } from "./header.js"`.
+fn rewrite_source(decl: &mut ModuleDecl) {
+    let src = match decl {
+        ModuleDecl::Import(import) => &mut import.src,
+        ModuleDecl::ExportNamed(NamedExport { src: Some(src), .. }) => src,
+        ModuleDecl::ExportAll(export) => &mut export.src,
+        _ => return,
+    };
+
+    let stem = match src.value.strip_suffix(".bmr") {
+        Some(stem) => stem.to_owned(),
+        None => return,
+    };
+    *src = Str::from(JsWord::from(format!("{}.js", stem)));
+
+    if let ModuleDecl::Import(import) = decl {
+        for specifier in &mut import.specifiers {
+            if let ImportSpecifier::Default(default) = specifier {
+                *specifier = ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: default.span,
+                    local: default.local.clone(),
+                    imported: Some(ModuleExportName::Ident(Ident::new(
+                        JsWord::from("render"),
+                        DUMMY_SP,
+                    ))),
+                    is_type_only: false,
+                });
+            }
+        }
     }
 }
 
-pub struct WithContext<'a> {
-    inner: &'a mut JSXTransform<'a>,
-    orig_ctx: JSXTransformContext,
+/// Collects every identifier referenced by a node. Scopes are not taken into
+/// account so a shadowed name counts as a reference.
+#[derive(Default)]
+struct IdentCollector {
+    idents: HashSet<JsWord>,
 }
 
-impl<'a> Deref for WithContext<'a> {
-    type Target = JSXTransform<'a>;
+impl IdentCollector {
+    fn contains(&self, sym: &JsWord) -> bool {
+        self.idents.contains(sym)
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        self.inner
+impl Visit for IdentCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.idents.insert(ident.sym.clone());
     }
 }
 
-impl<'a> DerefMut for WithContext<'a> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner
+/// The names bound by declarations, `a` and `b` in `const [a, b] = ...`.
+#[derive(Default)]
+struct BindingNames {
+    names: Vec<JsWord>,
+}
+
+impl Visit for BindingNames {
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.names.push(binding.id.sym.clone());
     }
+
+    // `const { a } = ...`
+    fn visit_assign_pat_prop(&mut self, prop: &AssignPatProp) {
+        self.names.push(prop.key.sym.clone());
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.names.push(fn_decl.ident.sym.clone());
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.names.push(class_decl.ident.sym.clone());
+    }
+
+    // `const { a = () => b } = ...` does not bind `b`
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// The identifiers code reads from the scopes around it, `alert` and `item`
+/// in `() => alert(item)`. Each name is listed once, at its first read.
+#[derive(Default)]
+struct FreeIdents {
+    scopes: Vec<Vec<JsWord>>,
+    idents: Vec<Ident>,
 }
 
-impl<'a> Drop for WithContext<'a> {
-    fn drop(&mut self) {
-        self.inner.set_ctx(self.orig_ctx);
+impl FreeIdents {
+    fn is_bound(&self, sym: &JsWord) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(sym))
+    }
+
+    fn scoped(&mut self, names: BindingNames, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(names.names);
+        visit(self);
+        self.scopes.pop();
     }
 }
 
-impl<'a> Visit for JSXTransform<'a> {
-    fn visit_jsx_element(&mut self, jsx_el: &JSXElement) {
-        match &jsx_el.opening.name {
-            JSXElementName::Ident(id) => {
-                if id
-                    .sym
-                    .chars()
-                    .nth(0)
-                    .map_or(false, |first_char| first_char.is_lowercase())
-                {
-                    self.cur_children = vec![];
+impl Visit for FreeIdents {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if !self.is_bound(&ident.sym) && !self.idents.iter().any(|read| read.sym == ident.sym) {
+            self.idents.push(ident.clone());
+        }
+    }
 
-                    self.jsx_el_stack.push(vec![]);
+    fn visit_function(&mut self, function: &Function) {
+        let mut names = BindingNames::default();
+        function.params.visit_with(&mut names);
+        function.body.visit_with(&mut names);
 
-                    jsx_el.visit_children_with(self);
+        self.scoped(names, |this| function.visit_children_with(this));
+    }
 
-                    let children: Vec<Option<ExprOrSpread>> =
-                        self.jsx_el_stack.pop().unwrap_or(vec![]);
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        let mut names = BindingNames::default();
+        arrow.params.visit_with(&mut names);
+        arrow.body.visit_with(&mut names);
 
-                    self.cur_children.push(html_open_tag!(&*id.sym));
-                    self.cur_children.push(array_lit!(children));
-                    self.cur_children.push(html_close_tag!(&*id.sym));
-                }
-            }
-            _ => (),
-        }
+        self.scoped(names, |this| arrow.visit_children_with(this));
     }
 
-    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
-        self.ctx.in_attribute_expr = true;
-        // TODO: if is client side attribute (e.g onClick)
-        attr.visit_children_with(self);
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        let names = BindingNames {
+            names: fn_expr.ident.iter().map(|id| id.sym.clone()).collect(),
+        };
+        self.scoped(names, |this| fn_expr.function.visit_with(this));
+    }
 
-        self.ctx.in_attribute_expr = false;
+    fn visit_class_expr(&mut self, class_expr: &ClassExpr) {
+        let names = BindingNames {
+            names: class_expr.ident.iter().map(|id| id.sym.clone()).collect(),
+        };
+        self.scoped(names, |this| class_expr.class.visit_with(this));
     }
 
-    fn visit_jsx_element_children(&mut self, children: &[JSXElementChild]) {
-        // here we have to ch
+    fn visit_catch_clause(&mut self, catch: &CatchClause) {
+        let mut names = BindingNames::default();
+        catch.param.visit_with(&mut names);
 
-        for child in children {
-            /*
-             * We are going to have to do two passes of the JSX
-             *
-             * 1. To identify the client side holes
-             * 2. To transform
-             *
-             * During 1. we should:
-             *
-             * 1. Record the spans of holes that need clientside reactivity.
-             * 2. Record the reactive dependants for that span
-             *
-             * During 2. when we come across a clientside hole we will need to track a bit more
-             *
-             * We need to:
-             * 1. Pull out a template that can be used for updating on the client with innerHTML
-             * 2. Attach selector to the element so we can find it in the DOM
-             * 3. Generate reactive get and set
-             */
-            let _span = child.span();
+        self.scoped(names, |this| catch.body.visit_with(this));
+    }
 
-            child.visit_with(self);
+    // `a.b` and `{ b: a }` only read `a`
+    fn visit_member_prop(&mut self, prop: &MemberProp) {
+        if let MemberProp::Computed(computed) = prop {
+            computed.visit_with(self);
         }
     }
 
-    fn visit_jsx_element_child(&mut self, child: &JSXElementChild) {
-        match child {
-            JSXElementChild::JSXText(t) => t.visit_with(self),
-            JSXElementChild::JSXExprContainer(e) => {
-                self.ctx.in_child_expr = true;
-                e.visit_with(self);
-                self.ctx.in_child_expr = false;
-            }
-            JSXElementChild::JSXSpreadChild(s) => s.visit_with(self),
-            JSXElementChild::JSXElement(e) => e.visit_with(self),
-            JSXElementChild::JSXFragment(f) => f.visit_with(self),
+    fn visit_super_prop(&mut self, prop: &SuperProp) {
+        if let SuperProp::Computed(computed) = prop {
+            computed.visit_with(self);
         }
     }
 
-    fn visit_jsx_expr_container(&mut self, expr_cont: &JSXExprContainer) {
-        if let JSXExpr::Expr(e) = &expr_cont.expr {
-            if let Some(last) = self.jsx_el_stack.last_mut() {
-                last.push(Some(ExprOrSpread {
-                    spread: None,
-                    expr: e.to_owned(),
-                }));
-            }
+    fn visit_prop_name(&mut self, name: &PropName) {
+        if let PropName::Computed(computed) = name {
+            computed.visit_with(self);
         }
     }
 
-    fn visit_call_expr(&mut self, call: &CallExpr) {}
+    fn visit_private_name(&mut self, _: &PrivateName) {}
 
-    fn visit_jsx_text(&mut self, txt: &JSXText) {
-        if let Some(last) = self.jsx_el_stack.last_mut() {
-            last.push(str_lit!(
-                JsWord::from(format!("`{}`", &*txt.value)),
-                txt.value.clone()
-            ));
-        }
+    fn visit_labeled_stmt(&mut self, stmt: &LabeledStmt) {
+        stmt.body.visit_with(self);
     }
+
+    fn visit_break_stmt(&mut self, _: &BreakStmt) {}
+
+    fn visit_continue_stmt(&mut self, _: &ContinueStmt) {}
 }
 
-struct ServerTransform;
+/// The functions called in an expression, `count` in `count() + 1` or
+/// `state[0]` in `state[0]() + 1`.
+#[derive(Default)]
+struct Callees {
+    callees: Vec<Expr>,
+}
 
-impl ServerTransform {
-    pub fn transform(server_block: ServerBlock) -> Vec<Stmt> {
-        server_block.block.stmts
+impl Visit for Callees {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            self.callees.push((**callee).clone());
+        }
+
+        call.visit_children_with(self);
     }
 }
 
-// struct ClientTransform;
-
-// impl ClientTransform {
-//     pub fn transform(&mut self, client_block: ClientBlock) ->
-// }
+macro_rules! html_close_tag {
+    ($span:expr, $tag:expr) => {
+        str_lit!($span, format!("</{}>", $tag))
+    };
+}
 
-// impl Visit for ClientTransform {
+macro_rules! str_lit {
+    ($span:expr, $value:expr) => {
+        Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Lit(Lit::Str(Str {
+                span: $span,
+                raw: None,
+                value: JsWord::from($value),
+            }))),
+        })
+    };
+}
 
-// }
+macro_rules! array_lit {
+    ($span:expr, $elems:expr) => {
+        Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Array(ArrayLit {
+                span: $span,
+                elems: $elems,
+            })),
+        })
+    };
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct JSXTransformContext {
+    // <button onClick={...
+    in_attribute_expr: bool,
+    // <div>{...
+    in_child_expr: bool,
+    // <pre>...
+    preserve_whitespace: bool,
+    // <html>...
+    in_document: bool,
+    // JSX in an expression or a server function, see `render_jsx`
+    in_expr_jsx: bool,
+    // <p>{count()}</p>, updated on the client as a whole
+    in_reactive_hole: bool,
+    // <title>..., children that cannot hold the markers of holes
+    raw_text: Option<&'static str>,
+    // the hole marking the element whose attributes are being rendered
+    attr_hole: Option<usize>,
+}
+
+/// How a page loads its client module.
+#[derive(Clone, Copy)]
+pub enum ClientScript<'a> {
+    /// `<script type="module">code</script>`
+    Inline(&'a str),
+    /// `<script type="module" src="url"></script>`
+    Src(&'a str),
+}
+
+/// The chunks of the page markup, rendered before the client module is
+/// generated so that it includes the event handlers of the page.
+pub struct RenderedRoot {
+    span: Span,
+    chunks: Vec<Option<ExprOrSpread>>,
+    document: bool,
+}
+
+impl RenderedRoot {
+    pub fn is_document(&self) -> bool {
+        self.document
+    }
+}
+
+/// What the client module wires up in the rendered page, see
+/// [JSXTransform::event_handler] and [JSXTransform::text_hole].
+#[derive(Default)]
+pub struct ClientBindings {
+    /// The handler expressions, indexed by the `data-bmr-<event>` markers
+    handlers: Vec<Expr>,
+    /// The events to listen for, e.g. `click`
+    events: BTreeSet<String>,
+    /// Children that read state, by the id of their `<!--bmr:id-->` marker
+    text_holes: Vec<(usize, Expr)>,
+    /// Attributes that read state, by the id of the `data-bmr-hole` marker
+    /// of their element
+    attr_holes: Vec<(usize, JsWord, Expr)>,
+    holes: usize,
+}
+
+impl ClientBindings {
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty() && !self.has_holes()
+    }
+
+    pub fn has_holes(&self) -> bool {
+        self.holes > 0
+    }
+
+    fn next_hole(&mut self) -> usize {
+        self.holes += 1;
+        self.holes - 1
+    }
+
+    /// The code of the bindings, which runs in the client module.
+    fn exprs(&self) -> impl Iterator<Item = &Expr> {
+        self.handlers
+            .iter()
+            .chain(self.text_holes.iter().map(|(_, expr)| expr))
+            .chain(self.attr_holes.iter().map(|(_, _, expr)| expr))
+    }
+}
+
+pub struct JSXTransform<'a> {
+    cur_children: Vec<Option<ExprOrSpread>>,
+
+    ctx: JSXTransformContext,
+
+    client_block: &'a Option<ClientBlock>,
+
+    // namespaces the markers of the file, see `marker_id`
+    module: &'a str,
+
+    // names that can be used as `<Component />`
+    components: HashSet<JsWord>,
+
+    // names that can hold components, `<Icons.Star />`
+    namespaces: HashSet<JsWord>,
+
+    jsx_el_stack: Vec<Vec<Option<ExprOrSpread>>>,
+
+    runtime: Runtime,
+
+    bindings: ClientBindings,
+
+    // the props `<slot />` renders the children of, the page's or those of
+    // the component being compiled
+    props: JsWord,
+
+    errors: Vec<ParserError>,
+}
+
+impl<'a> JSXTransform<'a> {
+    pub fn new(
+        client_block: &'a Option<ClientBlock>,
+        module: &'a str,
+        components: HashSet<JsWord>,
+        namespaces: HashSet<JsWord>,
+    ) -> Self {
+        Self {
+            cur_children: Default::default(),
+            ctx: Default::default(),
+            client_block,
+            module,
+            components,
+            namespaces,
+            jsx_el_stack: Default::default(),
+            runtime: Default::default(),
+            bindings: Default::default(),
+            props: JsWord::from("props"),
+            errors: Default::default(),
+        }
+    }
+
+    /// Renders the markup of the page, see [JSXTransform::render_function].
+    pub fn render_root(&mut self, jsx: &Option<JSXRoot>) -> RenderedRoot {
+        let span = jsx.as_ref().map_or(DUMMY_SP, |jsx| jsx.span());
+        let document = jsx.as_ref().is_some_and(is_document);
+
+        if let Some(jsx) = jsx {
+            self.ctx.in_document = document;
+
+            if let JSXRoot::Fragment(jsx_frag) = jsx {
+                if document && !has_child_element(&jsx_frag.children, "html") {
+                    self.document_head(&jsx_frag.children);
+                }
+            }
+
+            jsx.visit_with(self);
+            self.ctx.in_document = false;
+        }
+
+        RenderedRoot {
+            span,
+            chunks: self.cur_children.drain(..).collect(),
+            document,
+        }
+    }
+
+    /// The `render` function returning the chunks of `root` followed by the
+    /// `payload` of the client and the `client_script` loading the client
+    /// module, documents render both at the end of their body. The `state`
+    /// statements create the client state the markup reads.
+    pub fn render_function(
+        &mut self,
+        root: RenderedRoot,
+        client_script: Option<ClientScript>,
+        payload: Option<Expr>,
+        state: Vec<Stmt>,
+    ) -> Vec<ModuleDecl> {
+        let RenderedRoot {
+            span,
+            mut chunks,
+            document,
+        } = root;
+
+        let mut client = vec![];
+        if let Some(payload) = payload {
+            client.push(Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(payload),
+            }));
+        }
+        match client_script {
+            Some(ClientScript::Inline(code)) => client.push(str_lit!(
+                DUMMY_SP,
+                format!(r#"<script type="module">{}</script>"#, escape_script(code))
+            )),
+            Some(ClientScript::Src(url)) => client.push(str_lit!(
+                DUMMY_SP,
+                format!(
+                    r#"<script type="module" src="{}"></script>"#,
+                    escape_attr_value(url)
+                )
+            )),
+            None => (),
+        }
+
+        // other pages end with their client, so that the documents rendering
+        // them as components load it too
+        if !document {
+            chunks.append(&mut client);
+        }
+
+        let mut arr = Expr::Array(ArrayLit {
+            span,
+            elems: chunks,
+        });
+
+        if document {
+            arr = self.runtime.call(
+                Helper::Document,
+                span,
+                vec![
+                    arr,
+                    Expr::Array(ArrayLit {
+                        span: DUMMY_SP,
+                        elems: client,
+                    }),
+                ],
+            );
+        }
+
+        // Boomer files importing the page render it as a component
+        let markup = self.runtime.call(Helper::Markup, span, vec![arr]);
+
+        let mut stmts = state;
+        stmts.push(Stmt::Return(ReturnStmt {
+            span,
+            arg: Some(Box::new(markup)),
+        }));
+
+        let render_func = Decl::Fn(FnDecl {
+            ident: Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from("render"),
+                optional: false,
+            },
+            declare: false,
+            function: Function {
+                // `render(props = {})`
+                params: vec![Param::from(Pat::Assign(AssignPat {
+                    span: DUMMY_SP,
+                    left: Box::new(Pat::Ident(BindingIdent::from(Ident::new(
+                        JsWord::from("props"),
+                        DUMMY_SP,
+                    )))),
+                    right: Box::new(Expr::Object(ObjectLit {
+                        span: DUMMY_SP,
+                        props: vec![],
+                    })),
+                    type_ann: None,
+                }))],
+                decorators: vec![],
+                span: DUMMY_SP,
+                is_generator: false,
+                is_async: false,
+                type_params: None,
+                return_type: None,
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+            },
+        });
+
+        vec![ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: render_func,
+        })]
+    }
+
+    /// The chunks of the element being rendered, the top level chunks
+    /// when outside of an element.
+    fn chunks(&mut self) -> &mut Vec<Option<ExprOrSpread>> {
+        self.jsx_el_stack
+            .last_mut()
+            .unwrap_or(&mut self.cur_children)
+    }
+
+    /// The chunks of `<tag attr="value"...>`. Static attributes are folded
+    /// into the tag, dynamic ones are rendered by the runtime.
+    fn open_tag(&mut self, tag: &str, opening: &JSXOpeningElement) -> Vec<Option<ExprOrSpread>> {
+        let mut chunks = vec![];
+
+        push_str(&mut chunks, opening.span, &format!("<{}", tag));
+
+        let ctx = self.ctx;
+        if self.in_page_markup()
+            && opening.attrs.iter().any(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(attr) => {
+                    !is_inner_html(attr)
+                        && event_handler(attr).is_none()
+                        && attr_expr(attr).is_some_and(|expr| self.state_read(expr).is_some())
+                }
+                JSXAttrOrSpread::SpreadElement(_) => false,
+            })
+        {
+            let hole = self.bindings.next_hole();
+            push_str(
+                &mut chunks,
+                opening.span,
+                &format!(r#" data-bmr-hole="{}""#, marker_id(self.module, hole)),
+            );
+            self.ctx.attr_hole = Some(hole);
+        }
+
+        let has_spread = opening
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)));
+        // `<div id="a" {...attrs}>` merges the attributes in source order at
+        // runtime, the last value of an attribute wins
+        let mut merged = vec![];
+
+        for attr in &opening.attrs {
+            match attr {
+                JSXAttrOrSpread::JSXAttr(attr) => {
+                    if is_inner_html(attr) {
+                        continue;
+                    }
+
+                    if let Some((event, handler)) = event_handler(attr) {
+                        self.event_handler(event, handler, attr.span, &mut chunks);
+                        continue;
+                    }
+
+                    self.ctx.in_attribute_expr = true;
+                    if has_spread {
+                        merged.extend(self.merged_attr(attr));
+                    } else {
+                        self.transform_attr(attr, &mut chunks);
+                    }
+                    self.ctx.in_attribute_expr = false;
+                }
+                JSXAttrOrSpread::SpreadElement(spread) => {
+                    merged.push(PropOrSpread::Spread(SpreadElement {
+                        dot3_token: spread.dot3_token,
+                        expr: Box::new(self.compile_expr(&spread.expr)),
+                    }));
+                }
+            }
+        }
+
+        if has_spread {
+            let attrs = Expr::Object(ObjectLit {
+                span: opening.span,
+                props: merged,
+            });
+            let call = self
+                .runtime
+                .call(Helper::SpreadAttrs, opening.span, vec![attrs]);
+
+            chunks.push(Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(call),
+            }));
+        }
+
+        self.set_ctx(ctx);
+        push_str(&mut chunks, opening.span, ">");
+
+        chunks
+    }
+
+    /// Lifts `onClick={handler}` into the client module, the element is
+    /// marked with `data-bmr-click="<index of the handler>"` for the
+    /// delegated listeners to find it.
+    fn event_handler(
+        &mut self,
+        event: String,
+        handler: &Expr,
+        span: Span,
+        chunks: &mut Vec<Option<ExprOrSpread>>,
+    ) {
+        let index = self.bindings.handlers.len();
+        self.bindings.handlers.push(handler.clone());
+
+        push_str(
+            chunks,
+            span,
+            &format!(r#" data-bmr-{}="{}""#, event, marker_id(self.module, index)),
+        );
+        self.bindings.events.insert(event);
+    }
+
+    pub fn take_bindings(&mut self) -> ClientBindings {
+        std::mem::take(&mut self.bindings)
+    }
+
+    fn transform_attr(&mut self, attr: &JSXAttr, chunks: &mut Vec<Option<ExprOrSpread>>) {
+        let name = match &attr.name {
+            JSXAttrName::Ident(id) => Cow::Borrowed(html_attr_name(&id.sym)),
+            // `xlink:href`
+            JSXAttrName::JSXNamespacedName(name) => {
+                Cow::Owned(format!("{}:{}", name.ns.sym, name.name.sym))
+            }
+        };
+
+        let expr = match &attr.value {
+            None => return push_str(chunks, attr.span, &format!(" {}", name)),
+            Some(JSXAttrValue::Lit(lit)) => Cow::Owned(Expr::Lit(lit.clone())),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) => Cow::Borrowed(&**expr),
+            // `<div attr={/* comment */}>` and JSX values render nothing
+            Some(_) => return,
+        };
+
+        match &*expr {
+            Expr::Lit(Lit::Str(str)) => push_str(
+                chunks,
+                attr.span,
+                &format!(r#" {}="{}""#, name, escape_attr_value(&str.value)),
+            ),
+            Expr::Lit(Lit::Num(num)) => {
+                push_str(chunks, attr.span, &format!(r#" {}="{}""#, name, num.value))
+            }
+            Expr::Lit(Lit::Bool(bool)) => {
+                if bool.value {
+                    push_str(chunks, attr.span, &format!(" {}", name));
+                }
+            }
+            Expr::Lit(Lit::Null(_)) => (),
+            // `style={{ marginTop: 4 }}`
+            Expr::Object(obj) if name == "style" => match fold_style(obj) {
+                Some(css) if css.is_empty() => (),
+                Some(css) => push_str(
+                    chunks,
+                    attr.span,
+                    &format!(r#" style="{}""#, escape_attr_value(&css)),
+                ),
+                None => self.dynamic_attr(
+                    attr.span,
+                    "style",
+                    Some(Helper::Style),
+                    expr.into_owned(),
+                    chunks,
+                ),
+            },
+            // `class={["btn", { active }]}`
+            Expr::Array(_) | Expr::Object(_) if name == "class" => {
+                let (classes, dynamic) = fold_class(&expr);
+
+                if dynamic.is_empty() {
+                    if !classes.is_empty() {
+                        push_str(
+                            chunks,
+                            attr.span,
+                            &format!(r#" class="{}""#, escape_attr_value(&classes.join(" "))),
+                        );
+                    }
+                } else {
+                    let mut elems = vec![];
+                    if !classes.is_empty() {
+                        elems.push(str_lit!(attr.span, classes.join(" ")));
+                    }
+                    elems.extend(dynamic.into_iter().map(|expr| {
+                        Some(ExprOrSpread {
+                            spread: None,
+                            expr: Box::new(expr),
+                        })
+                    }));
+
+                    let value = Expr::Array(ArrayLit {
+                        span: expr.span(),
+                        elems,
+                    });
+                    self.dynamic_attr(attr.span, "class", Some(Helper::Class), value, chunks);
+                }
+            }
+            expr if name == "style" => self.dynamic_attr(
+                attr.span,
+                "style",
+                Some(Helper::Style),
+                expr.clone(),
+                chunks,
+            ),
+            expr if name == "class" => self.dynamic_attr(
+                attr.span,
+                "class",
+                Some(Helper::Class),
+                expr.clone(),
+                chunks,
+            ),
+            expr => self.dynamic_attr(attr.span, &name, None, expr.clone(), chunks),
+        }
+    }
+
+    /// The `name: value` of an attribute of an element with spread
+    /// attributes, `class` and `style` are formatted by the runtime.
+    fn merged_attr(&mut self, attr: &JSXAttr) -> Option<PropOrSpread> {
+        let name = match &attr.name {
+            JSXAttrName::Ident(id) => html_attr_name(&id.sym).to_owned(),
+            JSXAttrName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+        };
+
+        let value = match &attr.value {
+            None => Expr::Lit(Lit::Bool(Bool {
+                span: attr.span,
+                value: true,
+            })),
+            Some(JSXAttrValue::Lit(Lit::Str(str))) => Expr::Lit(Lit::Str(Str {
+                span: str.span,
+                value: str.value.clone(),
+                // the raw value has JSX entities which are not valid JavaScript
+                raw: None,
+            })),
+            Some(JSXAttrValue::Lit(lit)) => Expr::Lit(lit.clone()),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) => {
+                let helper = match &*name {
+                    "class" => Some(Helper::Class),
+                    "style" => Some(Helper::Style),
+                    _ => None,
+                };
+
+                self.attr_value(attr.span, &name, helper, expr)
+            }
+            Some(_) => return None,
+        };
+
+        Some(key_value(attr.span, JsWord::from(name), value))
+    }
+
+    /// Compiles the value of a dynamic attribute formatted by `helper`,
+    /// values that read state are bound to the element on the client.
+    fn attr_value(&mut self, span: Span, name: &str, helper: Option<Helper>, value: &Expr) -> Expr {
+        let hole = self
+            .ctx
+            .attr_hole
+            .filter(|_| self.state_read(value).is_some());
+
+        let ctx = self.ctx;
+        self.ctx.in_reactive_hole |= hole.is_some();
+        let value = self.compile_expr(value);
+        self.set_ctx(ctx);
+
+        let value = match helper {
+            Some(helper) => self.runtime.call(helper, span, vec![value]),
+            None => value,
+        };
+
+        if let Some(hole) = hole {
+            self.bindings
+                .attr_holes
+                .push((hole, JsWord::from(name), value.clone()));
+        }
+
+        value
+    }
+
+    /// Renders `name` with the value formatted by `helper` at runtime,
+    /// `$$attr("class", $$class(value))`. Values that read state are bound
+    /// to the element on the client.
+    fn dynamic_attr(
+        &mut self,
+        span: Span,
+        name: &str,
+        helper: Option<Helper>,
+        value: Expr,
+        chunks: &mut Vec<Option<ExprOrSpread>>,
+    ) {
+        let value = self.attr_value(span, name, helper, &value);
+
+        let call = self.runtime.call(
+            Helper::Attr,
+            span,
+            vec![Expr::Lit(Lit::Str(Str::from(JsWord::from(name)))), value],
+        );
+
+        chunks.push(Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(call),
+        }));
+    }
+
+    /// Renders an HTML, SVG or MathML element.
+    fn element(&mut self, tag: &str, jsx_el: &JSXElement) {
+        let in_document = self.ctx.in_document;
+
+        if tag == "head" && !in_document {
+            // merged into the head of the document rendering the page
+            let chunks = self.render_children(&jsx_el.children);
+            let head = self.runtime.call(
+                Helper::Head,
+                jsx_el.span,
+                vec![Expr::Array(ArrayLit {
+                    span: jsx_el.span,
+                    elems: chunks,
+                })],
+            );
+
+            self.chunks().push(Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(head),
+            }));
+            return;
+        }
+
+        let open_tag = self.open_tag(tag, &jsx_el.opening);
+
+        if is_void_element(tag) {
+            if has_children(jsx_el) {
+                self.emit_error(
+                    jsx_el.span,
+                    SyntaxError::VoidElementWithChildren(JsWord::from(tag)),
+                );
+            }
+
+            self.chunks().extend(open_tag);
+            return;
+        }
+
+        self.jsx_el_stack.push(vec![]);
+
+        if let Some(inner_html) = self.inner_html(&jsx_el.opening) {
+            // trusted markup replaces the children
+            self.chunks().push(Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(inner_html),
+            }));
+        } else {
+            let ctx = self.ctx;
+            if is_whitespace_sensitive(tag) {
+                self.ctx.preserve_whitespace = true;
+            }
+            if let Some(tag) = raw_text_element(tag) {
+                self.ctx.raw_text = Some(tag);
+            }
+            self.ctx.in_document = in_document && tag == "html";
+            if self.ctx.in_document && !has_child_element(&jsx_el.children, "head") {
+                self.document_head(&[]);
+            }
+            self.visit_jsx_element_children(&jsx_el.children);
+            self.set_ctx(ctx);
+        }
+
+        let mut children: Vec<Option<ExprOrSpread>> = self.jsx_el_stack.pop().unwrap_or_default();
+
+        if in_document && (tag == "head" || tag == "body") {
+            // where `$$document` adds the head content of the page and the
+            // scripts of the document
+            let region = self.runtime.call(
+                Helper::Region,
+                jsx_el.span,
+                vec![
+                    Expr::Lit(Lit::Str(Str::from(JsWord::from(tag)))),
+                    Expr::Array(ArrayLit {
+                        span: jsx_el.span,
+                        elems: children,
+                    }),
+                ],
+            );
+
+            children = vec![Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(region),
+            })];
+        }
+
+        let closing_span = jsx_el
+            .closing
+            .as_ref()
+            .map_or(jsx_el.span, |closing| closing.span);
+
+        let chunks = self.chunks();
+        chunks.extend(open_tag);
+        chunks.push(array_lit!(jsx_el.span, children));
+        chunks.push(html_close_tag!(closing_span, tag));
+    }
+
+    /// Adds an empty `<head>` to a document that does not have one, so there
+    /// is somewhere to merge the head content of the page.
+    fn document_head(&mut self, children: &[JSXElementChild]) {
+        if has_child_element(children, "head") {
+            return;
+        }
+
+        let region = self.runtime.call(
+            Helper::Region,
+            DUMMY_SP,
+            vec![
+                Expr::Lit(Lit::Str(Str::from(JsWord::from("head")))),
+                Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: vec![],
+                }),
+            ],
+        );
+
+        let chunks = self.chunks();
+        chunks.push(str_lit!(DUMMY_SP, "<head>"));
+        chunks.push(array_lit!(
+            DUMMY_SP,
+            vec![Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(region),
+            })]
+        ));
+        chunks.push(str_lit!(DUMMY_SP, "</head>"));
+    }
+
+    /// Renders `<Component prop={value} />` by calling the component with
+    /// its props, the markup it returns is streamed in place.
+    fn component(&mut self, callee: Expr, resolved: bool, jsx_el: &JSXElement) {
+        if !resolved {
+            let name = &jsx_el.opening.name;
+            self.emit_error(
+                name.span(),
+                SyntaxError::UnresolvedComponent(JsWord::from(element_name(name))),
+            );
+            return;
+        }
+
+        let mut props = self.props(&jsx_el.opening);
+        props.props.extend(self.children_props(jsx_el));
+
+        let call = Expr::Call(CallExpr {
+            span: jsx_el.span,
+            callee: Callee::Expr(Box::new(callee)),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Object(props)),
+            }],
+            type_args: None,
+        });
+        // components can return anything, only markup is not escaped
+        let call = self.runtime.call(Helper::Child, jsx_el.span, vec![call]);
+
+        self.chunks().push(Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(call),
+        }));
+    }
+
+    /// The attributes of a component as a props object.
+    fn props(&mut self, opening: &JSXOpeningElement) -> ObjectLit {
+        let mut props = vec![];
+
+        for attr in &opening.attrs {
+            let attr = match attr {
+                JSXAttrOrSpread::JSXAttr(attr) => attr,
+                // `<Component {...props} />`
+                JSXAttrOrSpread::SpreadElement(spread) => {
+                    props.push(PropOrSpread::Spread(SpreadElement {
+                        dot3_token: spread.dot3_token,
+                        expr: Box::new(self.compile_expr(&spread.expr)),
+                    }));
+                    continue;
+                }
+            };
+
+            let key = match &attr.name {
+                JSXAttrName::Ident(id) => id.sym.clone(),
+                JSXAttrName::JSXNamespacedName(name) => {
+                    JsWord::from(format!("{}:{}", name.ns.sym, name.name.sym))
+                }
+            };
+
+            let value = match &attr.value {
+                // `<Component disabled />`
+                None => Expr::Lit(Lit::Bool(Bool {
+                    span: attr.span,
+                    value: true,
+                })),
+                Some(JSXAttrValue::Lit(Lit::Str(str))) => Expr::Lit(Lit::Str(Str {
+                    span: str.span,
+                    value: str.value.clone(),
+                    // the raw value has JSX entities which are not valid JavaScript
+                    raw: None,
+                })),
+                Some(JSXAttrValue::Lit(lit)) => Expr::Lit(lit.clone()),
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) => self.compile_expr(expr),
+                Some(JSXAttrValue::JSXExprContainer(_)) => continue,
+                Some(JSXAttrValue::JSXElement(el)) => {
+                    self.compile_expr(&Expr::JSXElement(el.clone()))
+                }
+                Some(JSXAttrValue::JSXFragment(frag)) => {
+                    self.compile_expr(&Expr::JSXFragment(frag.clone()))
+                }
+            };
+
+            props.push(key_value(attr.span, key, value));
+        }
+
+        ObjectLit {
+            span: opening.span,
+            props,
+        }
+    }
+
+    /// Passes the children of a component as `children`, elements with a
+    /// `slot="name"` attribute are passed in `slots` instead.
+    fn children_props(&mut self, jsx_el: &JSXElement) -> Vec<PropOrSpread> {
+        let mut children = vec![];
+        let mut slots: Vec<(JsWord, Vec<JSXElementChild>)> = vec![];
+
+        for child in &jsx_el.children {
+            if let JSXElementChild::JSXElement(el) = child {
+                if let Some((name, el)) = take_slot(el) {
+                    match slots.iter_mut().find(|(slot, _)| *slot == name) {
+                        Some((_, content)) => content.push(el),
+                        None => slots.push((name, vec![el])),
+                    }
+                    continue;
+                }
+            }
+
+            children.push(child.clone());
+        }
+
+        let mut props = vec![];
+
+        let children = self.render_children(&children);
+        if !children.is_empty() {
+            let children = self.markup(jsx_el.span, children);
+            props.push(key_value(jsx_el.span, JsWord::from("children"), children));
+        }
+
+        if !slots.is_empty() {
+            let slots = slots
+                .into_iter()
+                .map(|(name, content)| {
+                    let span = content.first().map_or(jsx_el.span, |el| el.span());
+                    let content = self.render_children(&content);
+
+                    key_value(span, name, self.markup(span, content))
+                })
+                .collect();
+
+            props.push(key_value(
+                jsx_el.span,
+                JsWord::from("slots"),
+                Expr::Object(ObjectLit {
+                    span: jsx_el.span,
+                    props: slots,
+                }),
+            ));
+        }
+
+        props
+    }
+
+    /// `<slot />` renders the children passed to the page and
+    /// `<slot name="header" />` a named slot. The children of `<slot>` are
+    /// rendered when nothing was passed.
+    fn slot(&mut self, jsx_el: &JSXElement) {
+        let name = jsx_el.opening.attrs.iter().find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(id),
+                value: Some(value),
+                ..
+            }) if &*id.sym == "name" => match value {
+                JSXAttrValue::Lit(Lit::Str(str)) => Some(Expr::Lit(Lit::Str(Str {
+                    span: str.span,
+                    value: str.value.clone(),
+                    raw: None,
+                }))),
+                JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                }) => Some(self.compile_expr(expr)),
+                _ => None,
+            },
+            _ => None,
+        });
+
+        let mut args = vec![
+            Expr::Ident(Ident::new(self.props.clone(), DUMMY_SP)),
+            name.unwrap_or(Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))),
+        ];
+
+        let fallback = self.render_children(&jsx_el.children);
+        if !fallback.is_empty() {
+            args.push(self.markup(jsx_el.span, fallback));
+        }
+
+        let call = self.runtime.call(Helper::Slot, jsx_el.span, args);
+
+        self.chunks().push(Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(call),
+        }));
+    }
+
+    fn render_children(&mut self, children: &[JSXElementChild]) -> Vec<Option<ExprOrSpread>> {
+        self.jsx_el_stack.push(vec![]);
+        self.visit_jsx_element_children(children);
+        self.jsx_el_stack.pop().unwrap_or_default()
+    }
+
+    /// Chunks of rendered markup that are passed around as a value.
+    fn markup(&mut self, span: Span, chunks: Vec<Option<ExprOrSpread>>) -> Expr {
+        self.runtime.call(
+            Helper::Markup,
+            span,
+            vec![Expr::Array(ArrayLit {
+                span,
+                elems: chunks,
+            })],
+        )
+    }
+
+    /// Renders JSX found outside of the page markup, such as the markup
+    /// returned by a server function, to its chunks.
+    fn render_jsx(&mut self, expr: &Expr) -> Vec<Option<ExprOrSpread>> {
+        let ctx = self.ctx;
+        self.ctx.in_expr_jsx = true;
+        self.jsx_el_stack.push(vec![]);
+
+        match expr {
+            Expr::JSXElement(jsx_el) => self.visit_jsx_element(jsx_el),
+            Expr::JSXFragment(jsx_frag) => self.visit_jsx_fragment(jsx_frag),
+            _ => (),
+        }
+
+        self.set_ctx(ctx);
+        self.jsx_el_stack.pop().unwrap_or_default()
+    }
+
+    /// Compiles the JSX nested anywhere in `expr`, e.g.
+    /// `items.map((item) => <li>{item}</li>)`, to markup values.
+    pub fn compile_expr(&mut self, expr: &Expr) -> Expr {
+        // only holes are updated on the client
+        if !self.ctx.in_reactive_hole {
+            if let Some((span, getter)) = self.state_read(expr) {
+                self.emit_error(span, SyntaxError::StaticStateRead(getter));
+            }
+        }
+
+        let mut expr = expr.clone();
+
+        expr.visit_mut_with(&mut CompileJSX {
+            jsx_transform: self,
+        });
+
+        expr
+    }
+
+    /// Renders `{count()}` between `<!--bmr:id-->` and `<!--/bmr:id-->`
+    /// markers, the client renders it again between them when the state
+    /// it reads is set.
+    fn text_hole(&mut self, span: Span, expr: &Expr) {
+        let hole = self.bindings.next_hole();
+
+        let ctx = self.ctx;
+        self.ctx.in_reactive_hole = true;
+        let child = self.compile_expr(expr);
+        self.set_ctx(ctx);
+
+        let child = self.runtime.call(Helper::Child, span, vec![child]);
+        self.bindings.text_holes.push((hole, child.clone()));
+
+        let id = marker_id(self.module, hole);
+        let chunks = self.chunks();
+        push_str(chunks, span, &format!("<!--bmr:{}-->", id));
+        chunks.push(Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(child),
+        }));
+        push_str(chunks, span, &format!("<!--/bmr:{}-->", id));
+    }
+
+    /// Holes can be marked in the page markup, where each is rendered once.
+    fn in_page_markup(&self) -> bool {
+        !self.ctx.in_expr_jsx && !self.ctx.in_reactive_hole
+    }
+
+    /// The first getter of a `useState` that `expr` calls, and its span.
+    fn state_read(&self, expr: &Expr) -> Option<(Span, JsWord)> {
+        let mut calls = Callees::default();
+        expr.visit_with(&mut calls);
+
+        calls
+            .callees
+            .iter()
+            .find_map(|callee| Some((callee.span(), self.get_state(callee)?)))
+    }
+
+    /// `{...items}` flattens the rendered items into the chunks.
+    fn spread_child(&mut self, spread: &JSXSpreadChild) {
+        let items = self.compile_expr(&spread.expr);
+        let call = self.runtime.call(Helper::Child, spread.span, vec![items]);
+
+        self.chunks().push(Some(ExprOrSpread {
+            spread: Some(spread.span),
+            expr: Box::new(call),
+        }));
+    }
+
+    /// `<div innerHTML={html}>` opts out of escaping, the markup is
+    /// rendered as is instead of the element's children.
+    fn inner_html(&mut self, opening: &JSXOpeningElement) -> Option<Expr> {
+        let attr = opening.attrs.iter().find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(attr) if is_inner_html(attr) => Some(attr),
+            _ => None,
+        })?;
+
+        let expr = match attr.value.as_ref()? {
+            JSXAttrValue::Lit(lit) => Expr::Lit(lit.clone()),
+            JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            }) => self.compile_expr(expr),
+            _ => return None,
+        };
+
+        Some(self.runtime.call(Helper::Raw, attr.span, vec![expr]))
+    }
+
+    fn emit_error(&mut self, span: Span, error: SyntaxError) {
+        self.errors.push(ParserError::new(span, error));
+    }
+
+    pub fn take_errors(&mut self) -> Vec<ParserError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// The name of the getter `callee` is, `count` or `state[0]`.
+    fn get_state(&self, callee: &Expr) -> Option<JsWord> {
+        self.client_block.as_ref()?.use_state.getter(callee)
+    }
+
+    fn set_ctx(&mut self, ctx: JSXTransformContext) {
+        self.ctx = ctx;
+    }
+}
+
+impl<'a> Visit for JSXTransform<'a> {
+    fn visit_jsx_element(&mut self, jsx_el: &JSXElement) {
+        match &jsx_el.opening.name {
+            JSXElementName::Ident(id) if &*id.sym == "slot" => self.slot(jsx_el),
+            JSXElementName::Ident(id) if is_component_name(&id.sym) => {
+                let resolved = self.components.contains(&id.sym);
+                self.component(Expr::Ident(id.clone()), resolved, jsx_el)
+            }
+            JSXElementName::Ident(id) => self.element(&id.sym, jsx_el),
+            // `<svg:rect>`
+            JSXElementName::JSXNamespacedName(name) => {
+                self.element(&format!("{}:{}", name.ns.sym, name.name.sym), jsx_el)
+            }
+            // `<Icons.Star />`
+            JSXElementName::JSXMemberExpr(member) => {
+                let (callee, root) = member_expr(member);
+                let resolved =
+                    self.namespaces.contains(&root.sym) || self.components.contains(&root.sym);
+                self.component(callee, resolved, jsx_el)
+            }
+        }
+    }
+
+    fn visit_jsx_fragment(&mut self, jsx_frag: &JSXFragment) {
+        // fragments only group their children, they render no element
+        self.visit_jsx_element_children(&jsx_frag.children);
+    }
+
+    fn visit_jsx_element_children(&mut self, children: &[JSXElementChild]) {
+        // children that read state are marked as holes as they are
+        // rendered, see `text_hole`
+        for child in children {
+            child.visit_with(self);
+        }
+    }
+
+    fn visit_jsx_element_child(&mut self, child: &JSXElementChild) {
+        match child {
+            JSXElementChild::JSXText(t) => t.visit_with(self),
+            JSXElementChild::JSXExprContainer(e) => {
+                self.ctx.in_child_expr = true;
+                e.visit_with(self);
+                self.ctx.in_child_expr = false;
+            }
+            JSXElementChild::JSXSpreadChild(s) => self.spread_child(s),
+            JSXElementChild::JSXElement(e) => e.visit_with(self),
+            JSXElementChild::JSXFragment(f) => f.visit_with(self),
+        }
+    }
+
+    fn visit_jsx_expr_container(&mut self, expr_cont: &JSXExprContainer) {
+        let e = match &expr_cont.expr {
+            JSXExpr::Expr(e) => e,
+            JSXExpr::JSXEmptyExpr(_) => return,
+        };
+
+        // literals are escaped now rather than when rendering
+        let text = match &**e {
+            Expr::Lit(Lit::Str(str)) => Some(str.value.to_string()),
+            Expr::Lit(Lit::Num(num)) => Some(num.value.to_string()),
+            _ => None,
+        };
+
+        if let Some(text) = text {
+            push_str(self.chunks(), expr_cont.span, &escape_text(&text));
+            return;
+        }
+
+        // `{<b>bold</b>}` is rendered in place
+        if let Expr::JSXElement(_) | Expr::JSXFragment(_) = &**e {
+            let chunks = self.render_jsx(e);
+            self.chunks().extend(chunks);
+            return;
+        }
+
+        if self.in_page_markup() && self.state_read(e).is_some() {
+            return match self.ctx.raw_text {
+                Some(tag) => self.emit_error(
+                    expr_cont.span,
+                    SyntaxError::StateInRawText(JsWord::from(tag)),
+                ),
+                None => self.text_hole(expr_cont.span, e),
+            };
+        }
+
+        let child = self.compile_expr(e);
+        let child = self
+            .runtime
+            .call(Helper::Child, expr_cont.span, vec![child]);
+
+        self.chunks().push(Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(child),
+        }));
+    }
+
+    fn visit_jsx_text(&mut self, txt: &JSXText) {
+        let text = if self.ctx.preserve_whitespace {
+            Cow::Borrowed(&*txt.value)
+        } else {
+            Cow::Owned(collapse_whitespace(&txt.value))
+        };
+
+        if !text.is_empty() {
+            push_str(self.chunks(), txt.span, &escape_text(&text));
+        }
+    }
+}
+
+/// Appends `value` to the chunks, joining it onto the previous chunk when
+/// that is also a string.
+fn push_str(chunks: &mut Vec<Option<ExprOrSpread>>, span: Span, value: &str) {
+    if let Some(Some(ExprOrSpread { spread: None, expr })) = chunks.last_mut() {
+        if let Expr::Lit(Lit::Str(str)) = &mut **expr {
+            str.value = JsWord::from(format!("{}{}", str.value, value));
+            return;
+        }
+    }
+
+    chunks.push(str_lit!(span, value));
+}
+
+/// Applies the JSX whitespace rules: lines are trimmed, lines that are only
+/// whitespace are removed and the remaining lines are joined with a space.
+fn collapse_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.split(['\n', '\r']).collect();
+    let last = lines.len() - 1;
+    let mut collapsed: Vec<&str> = vec![];
+
+    for (i, line) in lines.iter().enumerate() {
+        let mut line = *line;
+
+        if i != 0 {
+            line = line.trim_start_matches([' ', '\t']);
+        }
+        if i != last {
+            line = line.trim_end_matches([' ', '\t']);
+        }
+
+        if !line.is_empty() {
+            collapsed.push(line);
+        }
+    }
+
+    collapsed.join(" ").replace('\t', " ")
+}
+
+/// Whether the page renders a whole document, `<html>` or the `<head>` and
+/// `<body>` in a fragment.
+fn is_document(root: &JSXRoot) -> bool {
+    match root {
+        JSXRoot::Element(jsx_el) => {
+            matches!(&jsx_el.opening.name, JSXElementName::Ident(id) if &*id.sym == "html")
+        }
+        JSXRoot::Fragment(jsx_frag) => ["html", "head", "body"]
+            .iter()
+            .any(|tag| has_child_element(&jsx_frag.children, tag)),
+    }
+}
+
+fn has_child_element(children: &[JSXElementChild], tag: &str) -> bool {
+    children.iter().any(|child| {
+        matches!(
+            child,
+            JSXElementChild::JSXElement(jsx_el)
+                if matches!(&jsx_el.opening.name, JSXElementName::Ident(id) if &*id.sym == tag)
+        )
+    })
+}
+
+/// Makes code safe to inline in a `<script>`, the HTML parser ends the script
+/// at the first `</script`.
+fn escape_script(code: &str) -> String {
+    let mut escaped = String::with_capacity(code.len());
+    let mut rest = code;
+
+    while let Some(i) = rest.to_ascii_lowercase().find("</script") {
+        escaped.push_str(&rest[..i + 1]);
+        escaped.push('\\');
+        rest = &rest[i + 1..];
+    }
+
+    escaped.push_str(rest);
+    escaped
+}
+
+/// Elements that cannot have children and have no closing tag.
+fn is_void_element(tag: &str) -> bool {
+    matches!(
+        tag,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Whether the element renders anything between its tags, whitespace that
+/// collapses away and `{/* comments */}` do not count.
+fn has_children(jsx_el: &JSXElement) -> bool {
+    let inner_html = jsx_el
+        .opening
+        .attrs
+        .iter()
+        .any(|attr| matches!(attr, JSXAttrOrSpread::JSXAttr(attr) if is_inner_html(attr)));
+
+    inner_html
+        || jsx_el.children.iter().any(|child| match child {
+            JSXElementChild::JSXText(txt) => !collapse_whitespace(&txt.value).is_empty(),
+            JSXElementChild::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::JSXEmptyExpr(_),
+                ..
+            }) => false,
+            _ => true,
+        })
+}
+
+/// Elements whose children are text, which shows comments as written,
+/// `<title><!--bmr:0-->1<!--/bmr:0--></title>`.
+fn raw_text_element(tag: &str) -> Option<&'static str> {
+    match tag {
+        "title" => Some("title"),
+        "textarea" => Some("textarea"),
+        "script" => Some("script"),
+        "style" => Some("style"),
+        _ => None,
+    }
+}
+
+/// Elements whose text is rendered as written.
+fn is_whitespace_sensitive(tag: &str) -> bool {
+    matches!(tag, "pre" | "textarea")
+}
+
+/// The CSS of a style object whose keys and values are all literals, the
+/// same as `$$style` renders.
+fn fold_style(obj: &ObjectLit) -> Option<String> {
+    let mut declarations = vec![];
+
+    for prop in &obj.props {
+        let (key, value) = match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::KeyValue(KeyValueProp { key, value }) => (static_key(key)?, &**value),
+                _ => return None,
+            },
+            PropOrSpread::Spread(_) => return None,
+        };
+
+        let value = match value {
+            Expr::Lit(Lit::Str(str)) if str.value.trim().is_empty() => continue,
+            Expr::Lit(Lit::Str(str)) => str.value.trim().to_owned(),
+            Expr::Lit(Lit::Num(num)) if num.value == 0.0 || is_unitless(&key) => {
+                num.value.to_string()
+            }
+            Expr::Lit(Lit::Num(num)) => format!("{}px", num.value),
+            Expr::Lit(Lit::Null(_) | Lit::Bool(_)) => continue,
+            Expr::Ident(id) if &*id.sym == "undefined" => continue,
+            _ => return None,
+        };
+
+        declarations.push(format!("{}:{}", css_property(&key), value));
+    }
+
+    Some(declarations.join(";"))
+}
+
+/// Splits a class array or object into the class names known at compile
+/// time and the expressions that `$$class` has to render.
+fn fold_class(expr: &Expr) -> (Vec<String>, Vec<Expr>) {
+    let mut classes = vec![];
+    let mut dynamic = vec![];
+
+    match expr {
+        Expr::Array(arr) => {
+            for elem in arr.elems.iter().flatten() {
+                match &*elem.expr {
+                    _ if elem.spread.is_some() => dynamic.push(Expr::Array(ArrayLit {
+                        span: arr.span,
+                        elems: vec![Some(elem.clone())],
+                    })),
+                    Expr::Lit(Lit::Str(str)) => {
+                        classes.extend(str.value.split_whitespace().map(|class| class.to_owned()))
+                    }
+                    Expr::Lit(Lit::Null(_) | Lit::Bool(_)) => (),
+                    Expr::Ident(id) if &*id.sym == "undefined" => (),
+                    expr @ (Expr::Array(_) | Expr::Object(_)) => {
+                        let (nested_classes, nested_dynamic) = fold_class(expr);
+                        classes.extend(nested_classes);
+                        dynamic.extend(nested_dynamic);
+                    }
+                    expr => dynamic.push(expr.clone()),
+                }
+            }
+        }
+        Expr::Object(obj) => {
+            let mut props = vec![];
+
+            for prop in &obj.props {
+                if let PropOrSpread::Prop(prop) = prop {
+                    if let Prop::KeyValue(KeyValueProp { key, value }) = &**prop {
+                        if let (Some(key), Expr::Lit(Lit::Bool(bool))) = (static_key(key), &**value)
+                        {
+                            if bool.value {
+                                classes.push(key);
+                            }
+                            continue;
+                        }
+                    }
+                }
+
+                props.push(prop.clone());
+            }
+
+            if !props.is_empty() {
+                dynamic.push(Expr::Object(ObjectLit {
+                    span: obj.span,
+                    props,
+                }));
+            }
+        }
+        expr => dynamic.push(expr.clone()),
+    }
+
+    (classes, dynamic)
+}
+
+fn static_key(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(id) => Some(id.sym.to_string()),
+        PropName::Str(str) => Some(str.value.to_string()),
+        _ => None,
+    }
+}
+
+/// `marginTop` as `margin-top`, custom properties are left as is.
+fn css_property(key: &str) -> String {
+    if key.starts_with("--") {
+        return key.to_owned();
+    }
+
+    let key = match key.strip_prefix("ms") {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_uppercase()) => format!("-ms{}", rest),
+        _ => key.to_owned(),
+    };
+
+    let mut property = String::with_capacity(key.len());
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            property.push('-');
+            property.push(c.to_ascii_lowercase());
+        } else {
+            property.push(c);
+        }
+    }
+
+    property
+}
+
+/// Style properties whose numbers are not lengths, these do not get `px`.
+fn is_unitless(key: &str) -> bool {
+    // `WebkitBoxFlex` is unitless because `boxFlex` is
+    let unprefixed = ["Webkit", "Moz", "ms", "O"].iter().find_map(|prefix| {
+        let rest = key.strip_prefix(prefix)?;
+        let first = rest.chars().next().filter(char::is_ascii_uppercase)?;
+
+        Some(format!("{}{}", first.to_ascii_lowercase(), &rest[1..]))
+    });
+
+    UNITLESS_STYLE_PROPERTIES.contains(&unprefixed.as_deref().unwrap_or(key))
+}
+
+// keep in sync with `$$unitless` in the runtime
+const UNITLESS_STYLE_PROPERTIES: &[&str] = &[
+    "animationIterationCount",
+    "aspectRatio",
+    "borderImageOutset",
+    "borderImageSlice",
+    "borderImageWidth",
+    "boxFlex",
+    "boxFlexGroup",
+    "boxOrdinalGroup",
+    "columnCount",
+    "columns",
+    "fillOpacity",
+    "flex",
+    "flexGrow",
+    "flexNegative",
+    "flexOrder",
+    "flexPositive",
+    "flexShrink",
+    "floodOpacity",
+    "fontWeight",
+    "gridArea",
+    "gridColumn",
+    "gridColumnEnd",
+    "gridColumnStart",
+    "gridRow",
+    "gridRowEnd",
+    "gridRowStart",
+    "lineClamp",
+    "lineHeight",
+    "opacity",
+    "order",
+    "orphans",
+    "scale",
+    "stopOpacity",
+    "strokeDasharray",
+    "strokeDashoffset",
+    "strokeMiterlimit",
+    "strokeOpacity",
+    "strokeWidth",
+    "tabSize",
+    "widows",
+    "zIndex",
+    "zoom",
+];
+
+/// Escapes static text, JSX has already decoded its entities.
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes a static attribute value, JSX has already decoded its entities.
+fn escape_attr_value(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+/// The event and handler of `onClick={handler}`. String values such as
+/// `onclick="..."` are rendered as attributes.
+fn event_handler(attr: &JSXAttr) -> Option<(String, &Expr)> {
+    let name = match &attr.name {
+        JSXAttrName::Ident(id) => &*id.sym,
+        JSXAttrName::JSXNamespacedName(_) => return None,
+    };
+
+    let event = name.strip_prefix("on")?;
+    if !event.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    match &attr.value {
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        })) => {
+            let event = match event {
+                "DoubleClick" => "dblclick".to_owned(),
+                event => event.to_ascii_lowercase(),
+            };
+
+            Some((event, &**expr))
+        }
+        _ => None,
+    }
+}
+
+/// The expression of `attr={expr}`.
+fn attr_expr(attr: &JSXAttr) -> Option<&Expr> {
+    match &attr.value {
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        })) => Some(expr),
+        _ => None,
+    }
+}
+
+fn is_inner_html(attr: &JSXAttr) -> bool {
+    matches!(&attr.name, JSXAttrName::Ident(id) if &*id.sym == "innerHTML")
+}
+
+/// `Icons.Star` as an expression, along with the `Icons` it is accessed on.
+fn member_expr(member: &JSXMemberExpr) -> (Expr, &Ident) {
+    let (obj, root) = match &member.obj {
+        JSXObject::Ident(id) => (Expr::Ident(id.clone()), id),
+        JSXObject::JSXMemberExpr(member) => member_expr(member),
+    };
+
+    let expr = Expr::Member(MemberExpr {
+        span: member.span(),
+        obj: Box::new(obj),
+        prop: MemberProp::Ident(member.prop.clone()),
+    });
+
+    (expr, root)
+}
+
+/// The name of an element as written in JSX.
+fn element_name(name: &JSXElementName) -> String {
+    fn object_name(obj: &JSXObject) -> String {
+        match obj {
+            JSXObject::Ident(id) => id.sym.to_string(),
+            JSXObject::JSXMemberExpr(member) => {
+                format!("{}.{}", object_name(&member.obj), member.prop.sym)
+            }
+        }
+    }
+
+    match name {
+        JSXElementName::Ident(id) => id.sym.to_string(),
+        JSXElementName::JSXMemberExpr(member) => {
+            format!("{}.{}", object_name(&member.obj), member.prop.sym)
+        }
+        JSXElementName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+    }
+}
+
+/// Whether `<name />` is a component rather than an HTML element.
+fn is_component_name(name: &str) -> bool {
+    !name
+        .chars()
+        .next()
+        .is_some_and(|first_char| first_char.is_lowercase())
+}
+
+fn key_value(span: Span, key: JsWord, value: Expr) -> PropOrSpread {
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: prop_name(span, key),
+        value: Box::new(value),
+    })))
+}
+
+/// The slot an element passed to a component fills, and the element
+/// without its `slot` attribute.
+fn take_slot(jsx_el: &JSXElement) -> Option<(JsWord, JSXElementChild)> {
+    let position = jsx_el.opening.attrs.iter().position(|attr| {
+        matches!(
+            attr,
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(id),
+                value: Some(JSXAttrValue::Lit(Lit::Str(_))),
+                ..
+            }) if &*id.sym == "slot"
+        )
+    })?;
+
+    let mut jsx_el = jsx_el.clone();
+
+    match jsx_el.opening.attrs.remove(position) {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            value: Some(JSXAttrValue::Lit(Lit::Str(name))),
+            ..
+        }) => Some((name.value, JSXElementChild::JSXElement(Box::new(jsx_el)))),
+        _ => None,
+    }
+}
+
+/// A props key, quoted when it is not a valid identifier such as `aria-label`.
+fn prop_name(span: Span, key: JsWord) -> PropName {
+    let is_ident = !key.starts_with(|c: char| c.is_ascii_digit())
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+
+    if is_ident {
+        PropName::Ident(Ident::new(key, span))
+    } else {
+        PropName::Str(Str {
+            span,
+            value: key,
+            raw: None,
+        })
+    }
+}
+
+/// Maps React style attribute names to their HTML and SVG equivalent.
+fn html_attr_name(name: &str) -> &str {
+    match name {
+        "className" => "class",
+        "htmlFor" => "for",
+        "xlinkHref" => "xlink:href",
+        "xmlnsXlink" => "xmlns:xlink",
+        "xmlLang" => "xml:lang",
+        "xmlSpace" => "xml:space",
+        "clipPath" => "clip-path",
+        "clipRule" => "clip-rule",
+        "fillOpacity" => "fill-opacity",
+        "fillRule" => "fill-rule",
+        "fontFamily" => "font-family",
+        "fontSize" => "font-size",
+        "fontWeight" => "font-weight",
+        "stopColor" => "stop-color",
+        "stopOpacity" => "stop-opacity",
+        "strokeDasharray" => "stroke-dasharray",
+        "strokeDashoffset" => "stroke-dashoffset",
+        "strokeLinecap" => "stroke-linecap",
+        "strokeLinejoin" => "stroke-linejoin",
+        "strokeMiterlimit" => "stroke-miterlimit",
+        "strokeOpacity" => "stroke-opacity",
+        "strokeWidth" => "stroke-width",
+        "textAnchor" => "text-anchor",
+        name => name,
+    }
+}
+
+struct ServerTransform;
+
+impl ServerTransform {
+    pub fn transform(server_block: &ServerBlock, jsx_transform: &mut JSXTransform) -> Vec<Stmt> {
+        let mut stmts = server_block.block.stmts.clone();
+
+        for stmt in &mut stmts {
+            match stmt {
+                Stmt::Decl(Decl::Fn(fn_decl)) => {
+                    Self::component(&mut fn_decl.function, jsx_transform)
+                }
+                Stmt::Decl(Decl::Var(var)) => {
+                    for decl in &mut var.decls {
+                        match decl.init.as_deref_mut() {
+                            Some(Expr::Fn(fn_expr)) => {
+                                Self::component(&mut fn_expr.function, jsx_transform)
+                            }
+                            Some(Expr::Arrow(arrow)) => Self::arrow_component(arrow, jsx_transform),
+                            _ => decl.visit_mut_with(&mut CompileJSX {
+                                jsx_transform: &mut *jsx_transform,
+                            }),
+                        }
+                    }
+                }
+                _ => stmt.visit_mut_with(&mut CompileJSX {
+                    jsx_transform: &mut *jsx_transform,
+                }),
+            }
+        }
+
+        stmts
+    }
+
+    fn component(function: &mut Function, jsx_transform: &mut JSXTransform) {
+        if has_slot(function) {
+            let mut params = function
+                .params
+                .iter()
+                .map(|param| param.pat.clone())
+                .collect();
+            let (props, pat) = bind_props(&mut params);
+            function.params = params.into_iter().map(Param::from).collect();
+
+            if let (Some(pat), Some(body)) = (pat, &mut function.body) {
+                body.stmts.insert(0, destructure_props(pat, &props));
+            }
+
+            jsx_transform.props = props;
+        }
+
+        function.visit_mut_with(&mut CompileJSX {
+            jsx_transform: &mut *jsx_transform,
+        });
+        jsx_transform.props = JsWord::from("props");
+    }
+
+    fn arrow_component(arrow: &mut ArrowExpr, jsx_transform: &mut JSXTransform) {
+        if has_slot(arrow) {
+            let (props, pat) = bind_props(&mut arrow.params);
+
+            if let Some(pat) = pat {
+                let destructure = destructure_props(pat, &props);
+
+                match &mut arrow.body {
+                    BlockStmtOrExpr::BlockStmt(body) => body.stmts.insert(0, destructure),
+                    BlockStmtOrExpr::Expr(expr) => {
+                        let expr = std::mem::replace(
+                            &mut **expr,
+                            Expr::Invalid(Invalid { span: DUMMY_SP }),
+                        );
+
+                        arrow.body = BlockStmtOrExpr::BlockStmt(BlockStmt {
+                            span: expr.span(),
+                            stmts: vec![
+                                destructure,
+                                Stmt::Return(ReturnStmt {
+                                    span: expr.span(),
+                                    arg: Some(Box::new(expr)),
+                                }),
+                            ],
+                        });
+                    }
+                }
+            }
+
+            jsx_transform.props = props;
+        }
+
+        arrow.visit_mut_with(&mut CompileJSX {
+            jsx_transform: &mut *jsx_transform,
+        });
+        jsx_transform.props = JsWord::from("props");
+    }
+}
+
+/// Whether `node` renders a `<slot />`.
+fn has_slot<N: VisitWith<SlotFinder>>(node: &N) -> bool {
+    let mut finder = SlotFinder::default();
+    node.visit_with(&mut finder);
+    finder.found
+}
+
+#[derive(Default)]
+struct SlotFinder {
+    found: bool,
+}
+
+impl Visit for SlotFinder {
+    fn visit_jsx_element_name(&mut self, name: &JSXElementName) {
+        if matches!(name, JSXElementName::Ident(id) if &*id.sym == "slot") {
+            self.found = true;
+        }
+    }
+}
+
+/// The name `<slot />` reads the children of a component from, its first
+/// parameter. Patterns are bound to `$$props` and returned to be
+/// destructured in the body instead, `function Box({ title }) {...}`
+/// becomes `function Box($$props) { let { title } = $$props; ... }`.
+fn bind_props(params: &mut Vec<Pat>) -> (JsWord, Option<Pat>) {
+    let props = JsWord::from("$$props");
+    let binding = Pat::Ident(BindingIdent::from(Ident::new(props.clone(), DUMMY_SP)));
+
+    let param = match params.first_mut() {
+        Some(param) => param,
+        None => {
+            params.push(binding);
+            return (props, None);
+        }
+    };
+
+    // `function Box({ title } = {})` keeps its default
+    let param = match param {
+        Pat::Assign(assign) => &mut *assign.left,
+        param => param,
+    };
+
+    match param {
+        Pat::Ident(id) => (id.id.sym.clone(), None),
+        param => (props, Some(std::mem::replace(param, binding))),
+    }
+}
+
+/// `let { title } = $$props;`
+fn destructure_props(pat: Pat, props: &JsWord) -> Stmt {
+    Stmt::Decl(Decl::Var(VarDecl {
+        span: DUMMY_SP,
+        kind: VarDeclKind::Let,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: pat,
+            init: Some(Box::new(Expr::Ident(Ident::new(props.clone(), DUMMY_SP)))),
+            definite: false,
+        }],
+    }))
+}
+
+/// Replaces JSX in code, such as the markup returned by a component, with
+/// `$$markup([...chunks])`.
+struct CompileJSX<'a, 'b> {
+    jsx_transform: &'a mut JSXTransform<'b>,
+}
+
+impl<'a, 'b> VisitMut for CompileJSX<'a, 'b> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::JSXElement(_) | Expr::JSXFragment(_) => {
+                let span = expr.span();
+                let chunks = self.jsx_transform.render_jsx(expr);
+
+                *expr = self.jsx_transform.markup(span, chunks);
+            }
+            _ => expr.visit_mut_children_with(self),
+        }
+    }
+}
+
+struct ClientTransform;
+
+impl ClientTransform {
+    pub fn transform(
+        client_block: &Option<ClientBlock>,
+        module: &str,
+        imports: Vec<ModuleDecl>,
+        bindings: ClientBindings,
+        values: Vec<JsWord>,
+        resume: bool,
+    ) -> Module {
+        let mut runtime = Runtime::default();
+        let mut stmts = vec![];
+
+        // `const value = $$resume(module, "values", "value")`
+        if !values.is_empty() {
+            let decls = values
+                .into_iter()
+                .map(|value| {
+                    let init = runtime.call(
+                        Helper::Resume,
+                        DUMMY_SP,
+                        vec![
+                            Expr::Lit(Lit::Str(Str::from(JsWord::from(module)))),
+                            Expr::Lit(Lit::Str(Str::from(JsWord::from("values")))),
+                            Expr::Lit(Lit::Str(Str::from(value.clone()))),
+                        ],
+                    );
+
+                    VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(BindingIdent::from(Ident::new(value, DUMMY_SP))),
+                        init: Some(Box::new(init)),
+                        definite: false,
+                    }
+                })
+                .collect();
+
+            stmts.push(Stmt::Decl(Decl::Var(VarDecl {
+                span: DUMMY_SP,
+                kind: VarDeclKind::Const,
+                declare: false,
+                decls,
+            })));
+        }
+
+        if let Some(client_block) = client_block {
+            let mut block_stmts = client_block.block.stmts.clone();
+            if resume {
+                resume_state(module, &mut block_stmts, &mut runtime);
+            }
+
+            stmts.extend(block_stmts);
+            stmts.visit_mut_with(&mut UseStateCalls {
+                runtime: &mut runtime,
+            });
+        }
+
+        let ClientBindings {
+            handlers,
+            events,
+            text_holes,
+            attr_holes,
+            ..
+        } = bindings;
+
+        if !handlers.is_empty() {
+            // `const $$handlers = [...]`, after the client block so the
+            // handlers can use its state
+            stmts.push(Stmt::Decl(Decl::Var(VarDecl {
+                span: DUMMY_SP,
+                kind: VarDeclKind::Const,
+                declare: false,
+                decls: vec![VarDeclarator {
+                    span: DUMMY_SP,
+                    name: Pat::Ident(BindingIdent::from(Ident::new(
+                        JsWord::from("$$handlers"),
+                        DUMMY_SP,
+                    ))),
+                    init: Some(Box::new(Expr::Array(ArrayLit {
+                        span: DUMMY_SP,
+                        elems: handlers
+                            .into_iter()
+                            .map(|handler| {
+                                Some(ExprOrSpread {
+                                    spread: None,
+                                    expr: Box::new(handler),
+                                })
+                            })
+                            .collect(),
+                    }))),
+                    definite: false,
+                }],
+            })));
+
+            let events = Expr::Array(ArrayLit {
+                span: DUMMY_SP,
+                elems: events
+                    .into_iter()
+                    .map(|event| str_lit!(DUMMY_SP, event))
+                    .collect(),
+            });
+            let delegate = runtime.call(
+                Helper::Delegate,
+                DUMMY_SP,
+                vec![
+                    Expr::Lit(Lit::Str(Str::from(JsWord::from(module)))),
+                    events,
+                    Expr::Ident(Ident::new(JsWord::from("$$handlers"), DUMMY_SP)),
+                ],
+            );
+
+            stmts.push(Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: Box::new(delegate),
+            }));
+        }
+
+        // `$$bindText("<module>:0", () => $$child(count()))`
+        for (hole, value) in text_holes {
+            runtime.track(&value);
+
+            let bind = runtime.call(
+                Helper::BindText,
+                DUMMY_SP,
+                vec![marker_lit(module, hole), thunk(value)],
+            );
+            stmts.push(Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: Box::new(bind),
+            }));
+        }
+
+        // `$$bindAttr("<module>:1", "class", () => $$class(...))`
+        for (hole, name, value) in attr_holes {
+            runtime.track(&value);
+
+            let bind = runtime.call(
+                Helper::BindAttr,
+                DUMMY_SP,
+                vec![
+                    marker_lit(module, hole),
+                    Expr::Lit(Lit::Str(Str::from(name))),
+                    thunk(value),
+                ],
+            );
+            stmts.push(Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: Box::new(bind),
+            }));
+        }
+
+        let mut module_items: Vec<ModuleItem> =
+            imports.into_iter().map(ModuleItem::ModuleDecl).collect();
+        module_items.extend(runtime.emit());
+        module_items.extend(stmts.into_iter().map(ModuleItem::Stmt));
+
+        Module {
+            shebang: None,
+            span: DUMMY_SP,
+            body: module_items,
+        }
+    }
+}
+
+/// The id of a marker, unique across the Boomer files rendered into a page,
+/// `<!--bmr:<module>:0-->` or `data-bmr-click="<module>:0"`.
+fn marker_id(module: &str, index: usize) -> String {
+    format!("{}:{}", module, index)
+}
+
+fn marker_lit(module: &str, index: usize) -> Expr {
+    Expr::Lit(Lit::Str(Str::from(JsWord::from(marker_id(module, index)))))
+}
+
+fn hole_id(hole: usize) -> Expr {
+    Expr::Lit(Lit::Num(Number {
+        span: DUMMY_SP,
+        value: hole as f64,
+        raw: None,
+    }))
+}
+
+/// `() => value`
+fn thunk(value: Expr) -> Expr {
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        params: vec![],
+        body: BlockStmtOrExpr::Expr(Box::new(value)),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+/// The `useState` declarations of the client block and their aliases,
+/// rendered on the server with their initial values. They are created at the
+/// start of `render` so every render computes its own initial state,
+/// `const [count, setCount] = $$serverState($$states, 0, () => 0)`.
+struct ServerState;
+
+impl ServerState {
+    fn transform(
+        parse_result: &ParseResult,
+        client_block: &ClientBlock,
+        jsx_transform: &mut JSXTransform,
+    ) -> Vec<Stmt> {
+        let mut server_names = parse_result
+            .server
+            .as_ref()
+            .map(|server| declared_names(&server.block.stmts))
+            .unwrap_or_default();
+        server_names.extend(import_names(parse_result));
+
+        let mut stmts = vec![];
+        let mut index = 0;
+
+        for stmt in &client_block.block.stmts {
+            let var = match stmt {
+                Stmt::Decl(Decl::Var(var)) => var,
+                _ => continue,
+            };
+
+            let mut decls = vec![];
+            for decl in &var.decls {
+                let init = match decl.init.as_deref() {
+                    Some(init) if client_block.use_state.is_state(init) => init,
+                    _ => continue,
+                };
+
+                // the markup of the page would read the state instead
+                let mut names = BindingNames::default();
+                decl.name.visit_with(&mut names);
+                for name in names.names {
+                    if server_names.contains(&name) {
+                        jsx_transform.emit_error(
+                            decl.name.span(),
+                            SyntaxError::StateShadowsServerBinding(name),
+                        );
+                    }
+                }
+
+                let init = match init {
+                    Expr::Call(call) if is_use_state_call(init) => {
+                        let initial = match call.args.first() {
+                            Some(arg) => (*arg.expr).clone(),
+                            None => Expr::Ident(Ident::new(JsWord::from("undefined"), DUMMY_SP)),
+                        };
+                        let state = jsx_transform.runtime.call(
+                            Helper::ServerState,
+                            call.span,
+                            vec![
+                                Expr::Ident(Ident::new(JsWord::from("$$states"), DUMMY_SP)),
+                                hole_id(index),
+                                thunk(initial),
+                            ],
+                        );
+                        index += 1;
+                        state
+                    }
+                    // aliases of the states declared before
+                    _ => init.clone(),
+                };
+
+                decls.push(VarDeclarator {
+                    init: Some(Box::new(init)),
+                    ..decl.clone()
+                });
+            }
+
+            if !decls.is_empty() {
+                stmts.push(Stmt::Decl(Decl::Var(VarDecl {
+                    decls,
+                    ..var.clone()
+                })));
+            }
+        }
+
+        if !stmts.is_empty() {
+            // `const $$states = {};` holds the initial values for the payload
+            stmts.insert(
+                0,
+                Stmt::Decl(Decl::Var(VarDecl {
+                    span: DUMMY_SP,
+                    kind: VarDeclKind::Const,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(BindingIdent::from(Ident::new(
+                            JsWord::from("$$states"),
+                            DUMMY_SP,
+                        ))),
+                        init: Some(Box::new(Expr::Object(ObjectLit {
+                            span: DUMMY_SP,
+                            props: vec![],
+                        }))),
+                        definite: false,
+                    }],
+                })),
+            );
+        }
+
+        stmts
+    }
+}
+
+/// The names a block declares at its top level.
+fn declared_names(stmts: &[Stmt]) -> Vec<JsWord> {
+    let mut names = BindingNames::default();
+
+    for stmt in stmts {
+        match stmt {
+            Stmt::Decl(Decl::Var(var)) => var.decls.visit_with(&mut names),
+            Stmt::Decl(Decl::Fn(fn_decl)) => names.names.push(fn_decl.ident.sym.clone()),
+            Stmt::Decl(Decl::Class(class_decl)) => names.names.push(class_decl.ident.sym.clone()),
+            _ => (),
+        }
+    }
+
+    names.names
+}
+
+/// `useState(0)` at the top level of the client block resumes from the
+/// state the page serialized, `useState($$resume(module, "state", 0, () => 0))`.
+/// The states are numbered in the order the server creates them, see
+/// [ServerState].
+fn resume_state(module: &str, stmts: &mut [Stmt], runtime: &mut Runtime) {
+    let mut index = 0;
+
+    for stmt in stmts {
+        let var = match stmt {
+            Stmt::Decl(Decl::Var(var)) => var,
+            _ => continue,
+        };
+
+        for decl in &mut var.decls {
+            let call = match decl.init.as_deref_mut() {
+                Some(init) if is_use_state_call(init) => match init {
+                    Expr::Call(call) => call,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            let initial = match call.args.drain(..).next() {
+                Some(arg) => *arg.expr,
+                None => Expr::Ident(Ident::new(JsWord::from("undefined"), DUMMY_SP)),
+            };
+
+            let resume = runtime.call(
+                Helper::Resume,
+                call.span,
+                vec![
+                    Expr::Lit(Lit::Str(Str::from(JsWord::from(module)))),
+                    Expr::Lit(Lit::Str(Str::from(JsWord::from("state")))),
+                    hole_id(index),
+                    thunk(initial),
+                ],
+            );
+            call.args = vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(resume),
+            }];
+
+            index += 1;
+        }
+    }
+}
+
+/// Replaces `useState(0)` with the runtime's `$$useState(0)`.
+struct UseStateCalls<'a> {
+    runtime: &'a mut Runtime,
+}
+
+impl<'a> VisitMut for UseStateCalls<'a> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        if is_use_state_call(expr) {
+            if let Expr::Call(call) = expr {
+                let args = call.args.drain(..).map(|arg| *arg.expr).collect();
+                *expr = self.runtime.call(Helper::UseState, call.span, args);
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Compiles every `tests/fixtures/<name>/input.bmr` and compares it with the
+//! `output.js` next to it, and `client.js` when the file has client code.
+//!
+//! Run with `UPDATE_FIXTURES=1` to write the current output instead.
+
+use std::{env, fs, path::Path};
+
+use compiler::{compile, CompileOptions};
+
+#[test]
+fn it_compiles_fixtures() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let update = env::var_os("UPDATE_FIXTURES").is_some();
+
+    let mut dirs: Vec<_> = fs::read_dir(&fixtures)
+        .expect("failed to read fixtures")
+        .map(|entry| entry.expect("failed to read fixture").path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    let mut failures = vec![];
+
+    for dir in dirs {
+        let input = fs::read_to_string(dir.join("input.bmr")).expect("failed to read input.bmr");
+
+        let output = match compile(&input, CompileOptions::default()) {
+            Ok(output) => output,
+            Err(diagnostics) => panic!("failed to compile {}: {:?}", dir.display(), diagnostics),
+        };
+
+        let expected = [
+            ("output.js", Some(output.code)),
+            ("client.js", output.client_code),
+        ];
+
+        for (name, actual) in expected {
+            let path = dir.join(name);
+
+            if update {
+                match actual {
+                    Some(actual) => fs::write(&path, actual).expect("failed to write fixture"),
+                    None => {
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+                continue;
+            }
+
+            let expected = fs::read_to_string(&path).ok();
+
+            if actual != expected {
+                failures.push(format!(
+                    "{}\n--- expected\n{}\n--- actual\n{}",
+                    path.display(),
+                    expected.unwrap_or_default(),
+                    actual.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "fixtures do not match, run with UPDATE_FIXTURES=1 to update them\n\n{}",
+        failures.join("\n\n")
+    );
+}
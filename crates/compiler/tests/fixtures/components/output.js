@@ -0,0 +1,80 @@
+import { render as Header } from "./header.js";
+import { Footer } from "./layout.js";
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const posts = [
+    {
+        title: "First",
+        body: "a < b"
+    }
+];
+function Badge({ label  }) {
+    return $$markup([
+        '<span class="badge">',
+        [
+            $$child(label)
+        ],
+        "</span>"
+    ]);
+}
+function Card({ title , body , featured  }) {
+    return ($$markup([
+        "<article>",
+        [
+            "<h2>",
+            [
+                $$child(title)
+            ],
+            "</h2>",
+            $$child(featured && "Featured"),
+            $$child(Badge({
+                label: title
+            })),
+            "<p>",
+            [
+                $$child(body)
+            ],
+            "</p>"
+        ],
+        "</article>"
+    ]));
+}
+export function render(props = {}) {
+    return $$markup([
+        $$child(Header({
+            title: "Posts & news",
+            "aria-label": "Site header"
+        })),
+        "<main>",
+        [
+            $$child(Card({
+                title: posts[0].title,
+                body: posts[0].body,
+                featured: true
+            }))
+        ],
+        "</main>",
+        $$child(Footer({
+            year: 2024
+        }))
+    ]);
+}
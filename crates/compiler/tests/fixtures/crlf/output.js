@@ -0,0 +1,47 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const code = "fn main() {}";
+export function render(props = {}) {
+    return $$markup([
+        "<div>",
+        [
+            "<p>",
+            [
+                "Lorem ipsum dolor sit"
+            ],
+            "</p>",
+            "<pre>",
+            [
+                "\r\n  first line\r\n\r\n      ",
+                $$child(code),
+                "\r\n\r\n\r\n  last line\r\n  "
+            ],
+            "</pre>",
+            "<textarea>",
+            [
+                "\r\n    one\r\n\r\n    two\r\n  "
+            ],
+            "</textarea>"
+        ],
+        "</div>"
+    ]);
+}
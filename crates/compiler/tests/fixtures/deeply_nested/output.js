@@ -0,0 +1,87 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const title = "Posts";
+const author = "Ada";
+export function render(props = {}) {
+    return $$markup([
+        "<main>",
+        [
+            "<header>",
+            [
+                "<nav>",
+                [
+                    "<ul>",
+                    [
+                        "<li>",
+                        [
+                            '<a href="/">',
+                            [
+                                "Home"
+                            ],
+                            "</a>"
+                        ],
+                        "</li>",
+                        "<li>",
+                        [
+                            '<a href="/posts">',
+                            [
+                                $$child(title)
+                            ],
+                            "</a>"
+                        ],
+                        "</li>"
+                    ],
+                    "</ul>"
+                ],
+                "</nav>"
+            ],
+            "</header>",
+            "<article>",
+            [
+                "<section>",
+                [
+                    "<div>",
+                    [
+                        "<p>",
+                        [
+                            "Written by ",
+                            "<em>",
+                            [
+                                "<strong>",
+                                [
+                                    $$child(author)
+                                ],
+                                "</strong>"
+                            ],
+                            "</em>"
+                        ],
+                        "</p>"
+                    ],
+                    "</div>"
+                ],
+                "</section>"
+            ],
+            "</article>"
+        ],
+        "</main>"
+    ]);
+}
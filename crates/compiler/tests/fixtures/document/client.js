@@ -0,0 +1,33 @@
+let $$observer = null;
+function $$useState(value) {
+    const observers = new Set();
+    const get = ()=>{
+        if ($$observer) observers.add($$observer);
+        return value;
+    };
+    const set = (next)=>{
+        next = typeof next === "function" ? next(value) : next;
+        if (Object.is(next, value)) return;
+        value = next;
+        for (const observer of [
+            ...observers
+        ])observer();
+    };
+    return [
+        get,
+        set
+    ];
+}
+let $$payload;
+function $$resume(module, kind, key, initial) {
+    if ($$payload === undefined) {
+        const script = document.getElementById("bmr-payload:" + module);
+        $$payload = script ? JSON.parse(script.textContent) : {};
+    }
+    const values = $$payload[kind];
+    if (values && key in values) return values[key];
+    return initial ? initial() : undefined;
+}
+const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0
+));
+console.log("</SCRIPT> count", count());
@@ -0,0 +1,146 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$head(chunks) {
+    chunks[Symbol.for("boomer.head")] = true;
+    return chunks;
+}
+function $$region(name, chunks) {
+    chunks[Symbol.for("boomer.region")] = name;
+    return chunks;
+}
+function $$document(chunks, bodyEnd) {
+    const head = [];
+    const regions = {};
+    const copy = (value)=>{
+        if (!Array.isArray(value)) return value;
+        if (value[Symbol.for("boomer.head")]) {
+            head.push(value.map(copy));
+            return "";
+        }
+        const chunks = value.map(copy);
+        const region = value[Symbol.for("boomer.region")];
+        if (region) regions[region] = chunks;
+        return chunks;
+    };
+    const html = copy(chunks);
+    (regions.head || html).unshift(...head);
+    (regions.body || html).push(...bodyEnd);
+    return [
+        "<!DOCTYPE html>",
+        html
+    ];
+}
+function $$serverState(states, index, initial) {
+    let value;
+    try {
+        value = initial();
+        states[index] = value;
+    } catch (error) {
+        if (!(error instanceof ReferenceError)) throw error;
+    }
+    return [
+        ()=>value
+        ,
+        ()=>{}
+    ];
+}
+function $$serialize(module, states, values) {
+    const check = (value, path)=>{
+        const type = typeof value;
+        if (value === null || type === "string" || type === "boolean") return;
+        if (type === "number" && Number.isFinite(value)) return;
+        if (Array.isArray(value)) {
+            value.forEach((item, index)=>check(item, path + "[" + index + "]")
+            );
+            return;
+        }
+        const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+        if (proto === Object.prototype || proto === null) {
+            for (const key of Object.keys(value)){
+                if (value[key] !== undefined) check(value[key], path + "." + key);
+            }
+            return;
+        }
+        const kind = type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+        throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+    };
+    for (const index of Object.keys(states)){
+        if (states[index] !== undefined) check(states[index], "useState #" + index);
+    }
+    for (const name of Object.keys(values)){
+        if (values[name] !== undefined) check(values[name], name);
+    }
+    const json = JSON.stringify({
+        state: states,
+        values
+    }).replace(/[<>&\u2028\u2029]/g, (c)=>"\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+    );
+    return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}
+function Seo({ title  }) {
+    return ($$markup([
+        $$head([
+            "<title>",
+            [
+                $$child(title)
+            ],
+            "</title>",
+            '<meta name="description" content="Posts &amp; news">'
+        ])
+    ]));
+}
+export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>0
+    );
+    return $$markup($$document([
+        '<html lang="en">',
+        [
+            "<head>",
+            [
+                $$region("head", [
+                    '<meta charset="utf-8">',
+                    '<link rel="stylesheet" href="/style.css">'
+                ])
+            ],
+            "</head>",
+            "<body>",
+            [
+                $$region("body", [
+                    $$child(Seo({
+                        title: "Posts"
+                    })),
+                    "<h1>",
+                    [
+                        "Posts"
+                    ],
+                    "</h1>"
+                ])
+            ],
+            "</body>"
+        ],
+        "</html>"
+    ], [
+        $$serialize("00c6c235", $$states, {}),
+        '<script type="module">let $$observer = null;\nfunction $$useState(value) {\n    const observers = new Set();\n    const get = ()=>{\n        if ($$observer) observers.add($$observer);\n        return value;\n    };\n    const set = (next)=>{\n        next = typeof next === "function" ? next(value) : next;\n        if (Object.is(next, value)) return;\n        value = next;\n        for (const observer of [\n            ...observers\n        ])observer();\n    };\n    return [\n        get,\n        set\n    ];\n}\nlet $$payload;\nfunction $$resume(module, kind, key, initial) {\n    if ($$payload === undefined) {\n        const script = document.getElementById("bmr-payload:" + module);\n        $$payload = script ? JSON.parse(script.textContent) : {};\n    }\n    const values = $$payload[kind];\n    if (values && key in values) return values[key];\n    return initial ? initial() : undefined;\n}\nconst [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0\n));\nconsole.log("<\\/SCRIPT> count", count());\n</script>'
+    ]));
+}
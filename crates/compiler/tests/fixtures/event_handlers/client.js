@@ -0,0 +1,75 @@
+let $$observer = null;
+function $$useState(value) {
+    const observers = new Set();
+    const get = ()=>{
+        if ($$observer) observers.add($$observer);
+        return value;
+    };
+    const set = (next)=>{
+        next = typeof next === "function" ? next(value) : next;
+        if (Object.is(next, value)) return;
+        value = next;
+        for (const observer of [
+            ...observers
+        ])observer();
+    };
+    return [
+        get,
+        set
+    ];
+}
+const $$captured = new Set([
+    "blur",
+    "error",
+    "focus",
+    "load",
+    "mouseenter",
+    "mouseleave",
+    "scroll"
+]);
+function $$delegate(module, events, handlers) {
+    const prefix = module + ":";
+    for (const event of events){
+        const marker = "data-bmr-" + event;
+        const captured = $$captured.has(event);
+        document.addEventListener(event, (e)=>{
+            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){
+                const id = node.getAttribute(marker);
+                if (id !== null && id.startsWith(prefix)) {
+                    handlers[id.slice(prefix.length)].call(node, e);
+                    if (e.cancelBubble) return;
+                }
+                if (captured) return;
+            }
+        }, captured);
+    }
+}
+let $$payload;
+function $$resume(module, kind, key, initial) {
+    if ($$payload === undefined) {
+        const script = document.getElementById("bmr-payload:" + module);
+        $$payload = script ? JSON.parse(script.textContent) : {};
+    }
+    const values = $$payload[kind];
+    if (values && key in values) return values[key];
+    return initial ? initial() : undefined;
+}
+const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0
+));
+function reset() {
+    setCount(0);
+}
+const $$handlers = [
+    (e)=>e.preventDefault()
+    ,
+    (e)=>e.target.select()
+    ,
+    reset,
+    ()=>setCount((n)=>n + 1
+        )
+];
+$$delegate("00c6c235", [
+    "click",
+    "focus",
+    "submit"
+], $$handlers);
@@ -0,0 +1,108 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$serverState(states, index, initial) {
+    let value;
+    try {
+        value = initial();
+        states[index] = value;
+    } catch (error) {
+        if (!(error instanceof ReferenceError)) throw error;
+    }
+    return [
+        ()=>value
+        ,
+        ()=>{}
+    ];
+}
+function $$serialize(module, states, values) {
+    const check = (value, path)=>{
+        const type = typeof value;
+        if (value === null || type === "string" || type === "boolean") return;
+        if (type === "number" && Number.isFinite(value)) return;
+        if (Array.isArray(value)) {
+            value.forEach((item, index)=>check(item, path + "[" + index + "]")
+            );
+            return;
+        }
+        const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+        if (proto === Object.prototype || proto === null) {
+            for (const key of Object.keys(value)){
+                if (value[key] !== undefined) check(value[key], path + "." + key);
+            }
+            return;
+        }
+        const kind = type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+        throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+    };
+    for (const index of Object.keys(states)){
+        if (states[index] !== undefined) check(states[index], "useState #" + index);
+    }
+    for (const name of Object.keys(values)){
+        if (values[name] !== undefined) check(values[name], name);
+    }
+    const json = JSON.stringify({
+        state: states,
+        values
+    }).replace(/[<>&\u2028\u2029]/g, (c)=>"\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+    );
+    return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}
+function Counter({ label  }) {
+    return $$markup([
+        '<button data-bmr-click="00c6c235:3">',
+        [
+            $$child(label)
+        ],
+        "</button>"
+    ]);
+}
+export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>0
+    );
+    return $$markup([
+        "<main>",
+        [
+            $$child(Counter({
+                label: "Add"
+            })),
+            '<form data-bmr-submit="00c6c235:0">',
+            [
+                '<input data-bmr-focus="00c6c235:1" value="a">',
+                '<button type="reset" data-bmr-click="00c6c235:2">',
+                [
+                    "Reset"
+                ],
+                "</button>"
+            ],
+            "</form>",
+            '<a href="/" onclick="track()">',
+            [
+                "Home"
+            ],
+            "</a>"
+        ],
+        "</main>",
+        $$serialize("00c6c235", $$states, {}),
+        '<script type="module">let $$observer = null;\nfunction $$useState(value) {\n    const observers = new Set();\n    const get = ()=>{\n        if ($$observer) observers.add($$observer);\n        return value;\n    };\n    const set = (next)=>{\n        next = typeof next === "function" ? next(value) : next;\n        if (Object.is(next, value)) return;\n        value = next;\n        for (const observer of [\n            ...observers\n        ])observer();\n    };\n    return [\n        get,\n        set\n    ];\n}\nconst $$captured = new Set([\n    "blur",\n    "error",\n    "focus",\n    "load",\n    "mouseenter",\n    "mouseleave",\n    "scroll"\n]);\nfunction $$delegate(module, events, handlers) {\n    const prefix = module + ":";\n    for (const event of events){\n        const marker = "data-bmr-" + event;\n        const captured = $$captured.has(event);\n        document.addEventListener(event, (e)=>{\n            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){\n                const id = node.getAttribute(marker);\n                if (id !== null && id.startsWith(prefix)) {\n                    handlers[id.slice(prefix.length)].call(node, e);\n                    if (e.cancelBubble) return;\n                }\n                if (captured) return;\n            }\n        }, captured);\n    }\n}\nlet $$payload;\nfunction $$resume(module, kind, key, initial) {\n    if ($$payload === undefined) {\n        const script = document.getElementById("bmr-payload:" + module);\n        $$payload = script ? JSON.parse(script.textContent) : {};\n    }\n    const values = $$payload[kind];\n    if (values && key in values) return values[key];\n    return initial ? initial() : undefined;\n}\nconst [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0\n));\nfunction reset() {\n    setCount(0);\n}\nconst $$handlers = [\n    (e)=>e.preventDefault()\n    ,\n    (e)=>e.target.select()\n    ,\n    reset,\n    ()=>setCount((n)=>n + 1\n        )\n];\n$$delegate("00c6c235", [\n    "click",\n    "focus",\n    "submit"\n], $$handlers);\n</script>'
+    ]);
+}
@@ -0,0 +1,109 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$attr(name, value) {
+    if (value == null || value === false || typeof value === "function") return "";
+    if (value === true) return " " + name;
+    return " " + name + '="' + $$escape(value) + '"';
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const items = [
+    "<one>",
+    "two"
+];
+const loggedIn = true;
+const user = {
+    name: "Ada",
+    admin: false
+};
+function Nav({ links  }) {
+    return $$markup([
+        "<nav>",
+        [
+            $$child(links.map((link)=>$$markup([
+                    "<a",
+                    $$attr("href", link),
+                    ">",
+                    [
+                        $$child(link)
+                    ],
+                    "</a>"
+                ])
+            ))
+        ],
+        "</nav>"
+    ]);
+}
+export function render(props = {}) {
+    return $$markup([
+        "<main>",
+        [
+            $$child(loggedIn && $$markup([
+                $$child(Nav({
+                    links: [
+                        "/",
+                        "/about"
+                    ]
+                }))
+            ])),
+            $$child(user.admin ? $$markup([
+                "<b>",
+                [
+                    "admin"
+                ],
+                "</b>"
+            ]) : $$markup([
+                "<i>",
+                [
+                    $$child(user.name)
+                ],
+                "</i>"
+            ])),
+            "<ul>",
+            [
+                $$child(items.map((item, i)=>($$markup([
+                        "<li>",
+                        [
+                            $$child(i),
+                            ": ",
+                            $$child(item),
+                            $$child(i === 0 ? $$markup([
+                                "<em>",
+                                [
+                                    "first"
+                                ],
+                                "</em>!"
+                            ]) : null)
+                        ],
+                        "</li>"
+                    ]))
+                ))
+            ],
+            "</ul>",
+            "<p>",
+            [
+                "inline"
+            ],
+            "</p>",
+            $$child(items.length === 0 && "nothing")
+        ],
+        "</main>"
+    ]);
+}
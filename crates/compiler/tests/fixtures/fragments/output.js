@@ -0,0 +1,109 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$region(name, chunks) {
+    chunks[Symbol.for("boomer.region")] = name;
+    return chunks;
+}
+function $$document(chunks, bodyEnd) {
+    const head = [];
+    const regions = {};
+    const copy = (value)=>{
+        if (!Array.isArray(value)) return value;
+        if (value[Symbol.for("boomer.head")]) {
+            head.push(value.map(copy));
+            return "";
+        }
+        const chunks = value.map(copy);
+        const region = value[Symbol.for("boomer.region")];
+        if (region) regions[region] = chunks;
+        return chunks;
+    };
+    const html = copy(chunks);
+    (regions.head || html).unshift(...head);
+    (regions.body || html).push(...bodyEnd);
+    return [
+        "<!DOCTYPE html>",
+        html
+    ];
+}
+const title = "Fragments";
+const items = [
+    "a",
+    "b"
+];
+export function render(props = {}) {
+    return $$markup($$document([
+        "<head>",
+        [
+            $$region("head", [
+                "<title>",
+                [
+                    $$child(title)
+                ],
+                "</title>"
+            ])
+        ],
+        "</head>",
+        "<body>",
+        [
+            $$region("body", [
+                "<h1>",
+                [
+                    $$child(title)
+                ],
+                "</h1>",
+                "<p>",
+                [
+                    "first"
+                ],
+                "</p>",
+                "<p>",
+                [
+                    "second"
+                ],
+                "</p>",
+                "<ul>",
+                [
+                    "<li>",
+                    [
+                        "one"
+                    ],
+                    "</li>",
+                    "<li>",
+                    [
+                        "two"
+                    ],
+                    "</li>"
+                ],
+                "</ul>",
+                "<p>",
+                [
+                    "before inside ",
+                    $$child(items),
+                    " after"
+                ],
+                "</p>"
+            ])
+        ],
+        "</body>"
+    ], []));
+}
@@ -0,0 +1,58 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const name = "<World>";
+const count = 3;
+export function render(props = {}) {
+    return $$markup([
+        '<p class="greeting">',
+        [
+            "Hello ",
+            $$child(name),
+            ", you have ",
+            "<b>",
+            [
+                $$child(count)
+            ],
+            "</b> new messages &amp; ",
+            "<i>",
+            [
+                $$child(count)
+            ],
+            "</i> alerts.",
+            "<br>Bye ",
+            "<span>",
+            [
+                "<span>",
+                [
+                    "<span>",
+                    [
+                        $$child(name)
+                    ],
+                    "</span>"
+                ],
+                "</span>"
+            ],
+            "</span>!"
+        ],
+        "</p>"
+    ]);
+}
@@ -0,0 +1,56 @@
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$region(name, chunks) {
+    chunks[Symbol.for("boomer.region")] = name;
+    return chunks;
+}
+function $$document(chunks, bodyEnd) {
+    const head = [];
+    const regions = {};
+    const copy = (value)=>{
+        if (!Array.isArray(value)) return value;
+        if (value[Symbol.for("boomer.head")]) {
+            head.push(value.map(copy));
+            return "";
+        }
+        const chunks = value.map(copy);
+        const region = value[Symbol.for("boomer.region")];
+        if (region) regions[region] = chunks;
+        return chunks;
+    };
+    const html = copy(chunks);
+    (regions.head || html).unshift(...head);
+    (regions.body || html).push(...bodyEnd);
+    return [
+        "<!DOCTYPE html>",
+        html
+    ];
+}
+export function render(props = {}) {
+    return $$markup($$document([
+        "<head>",
+        [
+            $$region("head", [
+                "<title>",
+                [
+                    "Page"
+                ],
+                "</title>"
+            ])
+        ],
+        "</head>",
+        "<body>",
+        [
+            $$region("body", [
+                "<main>",
+                [
+                    "Hello"
+                ],
+                "</main>"
+            ])
+        ],
+        "</body>"
+    ], []));
+}
@@ -0,0 +1,179 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$class(value) {
+    let classes;
+    if (Array.isArray(value)) {
+        classes = value.map($$class).filter(Boolean).join(" ");
+    } else if (value != null && typeof value === "object") {
+        classes = Object.keys(value).filter((key)=>value[key]
+        ).join(" ");
+    } else {
+        classes = value == null || typeof value === "boolean" ? "" : String(value);
+    }
+    return classes === "" ? null : classes;
+}
+let $$observer = null;
+function $$useState(value) {
+    const observers = new Set();
+    const get = ()=>{
+        if ($$observer) observers.add($$observer);
+        return value;
+    };
+    const set = (next)=>{
+        next = typeof next === "function" ? next(value) : next;
+        if (Object.is(next, value)) return;
+        value = next;
+        for (const observer of [
+            ...observers
+        ])observer();
+    };
+    return [
+        get,
+        set
+    ];
+}
+const $$captured = new Set([
+    "blur",
+    "error",
+    "focus",
+    "load",
+    "mouseenter",
+    "mouseleave",
+    "scroll"
+]);
+function $$delegate(module, events, handlers) {
+    const prefix = module + ":";
+    for (const event of events){
+        const marker = "data-bmr-" + event;
+        const captured = $$captured.has(event);
+        document.addEventListener(event, (e)=>{
+            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){
+                const id = node.getAttribute(marker);
+                if (id !== null && id.startsWith(prefix)) {
+                    handlers[id.slice(prefix.length)].call(node, e);
+                    if (e.cancelBubble) return;
+                }
+                if (captured) return;
+            }
+        }, captured);
+    }
+}
+function $$effect(compute, apply) {
+    let rendered = false;
+    const run = ()=>{
+        const observer = $$observer;
+        $$observer = run;
+        let value;
+        try {
+            value = compute();
+        } finally{
+            $$observer = observer;
+        }
+        if (rendered) apply(value);
+        rendered = true;
+    };
+    run();
+}
+let $$holes;
+function $$bindText(id, value) {
+    if (!$$holes) {
+        $$holes = {};
+        const walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);
+        while(walker.nextNode()){
+            const match = /^bmr:(.+)$/.exec(walker.currentNode.data);
+            if (match) ($$holes[match[1]] ||= []).push(walker.currentNode);
+        }
+    }
+    const starts = $$holes[id] || [];
+    $$effect(value, (chunks)=>{
+        const html = [
+            chunks
+        ].flat(Infinity).join("");
+        for (const start of starts){
+            while(start.nextSibling && start.nextSibling.data !== "/bmr:" + id){
+                start.nextSibling.remove();
+            }
+            const template = document.createElement("template");
+            template.innerHTML = html;
+            start.after(template.content);
+        }
+    });
+}
+function $$bindAttr(id, name, value) {
+    const elements = document.querySelectorAll('[data-bmr-hole="' + id + '"]');
+    $$effect(value, (v)=>{
+        const removed = v == null || v === false || typeof v === "function";
+        for (const element of elements){
+            if (removed) element.removeAttribute(name);
+            else element.setAttribute(name, v === true ? "" : String(v));
+            if (name === "value") element.value = removed ? "" : String(v);
+            if (name === "checked") element.checked = !removed;
+        }
+    });
+}
+let $$payload;
+function $$resume(module, kind, key, initial) {
+    if ($$payload === undefined) {
+        const script = document.getElementById("bmr-payload:" + module);
+        $$payload = script ? JSON.parse(script.textContent) : {};
+    }
+    const values = $$payload[kind];
+    if (values && key in values) return values[key];
+    return initial ? initial() : undefined;
+}
+const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0
+));
+const [name, setName] = $$useState($$resume("00c6c235", "state", 1, ()=>"world"
+));
+const $$handlers = [
+    (e)=>setName(e.target.value)
+    ,
+    ()=>setCount((n)=>n + 1
+        )
+];
+$$delegate("00c6c235", [
+    "click",
+    "input"
+], $$handlers);
+$$bindText("00c6c235:1", ()=>$$child(name())
+);
+$$bindText("00c6c235:3", ()=>$$child(count())
+);
+$$bindText("00c6c235:4", ()=>$$child(count() > 2 && $$markup([
+        "<strong>",
+        [
+            "Many ",
+            $$child(count())
+        ],
+        "</strong>"
+    ]))
+);
+$$bindAttr("00c6c235:0", "title", ()=>name()
+);
+$$bindAttr("00c6c235:2", "class", ()=>$$class([
+        {
+            odd: count() % 2 === 1
+        }
+    ])
+);
+$$bindAttr("00c6c235:5", "value", ()=>name()
+);
@@ -0,0 +1,142 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$attr(name, value) {
+    if (value == null || value === false || typeof value === "function") return "";
+    if (value === true) return " " + name;
+    return " " + name + '="' + $$escape(value) + '"';
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$class(value) {
+    let classes;
+    if (Array.isArray(value)) {
+        classes = value.map($$class).filter(Boolean).join(" ");
+    } else if (value != null && typeof value === "object") {
+        classes = Object.keys(value).filter((key)=>value[key]
+        ).join(" ");
+    } else {
+        classes = value == null || typeof value === "boolean" ? "" : String(value);
+    }
+    return classes === "" ? null : classes;
+}
+function $$serverState(states, index, initial) {
+    let value;
+    try {
+        value = initial();
+        states[index] = value;
+    } catch (error) {
+        if (!(error instanceof ReferenceError)) throw error;
+    }
+    return [
+        ()=>value
+        ,
+        ()=>{}
+    ];
+}
+function $$serialize(module, states, values) {
+    const check = (value, path)=>{
+        const type = typeof value;
+        if (value === null || type === "string" || type === "boolean") return;
+        if (type === "number" && Number.isFinite(value)) return;
+        if (Array.isArray(value)) {
+            value.forEach((item, index)=>check(item, path + "[" + index + "]")
+            );
+            return;
+        }
+        const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+        if (proto === Object.prototype || proto === null) {
+            for (const key of Object.keys(value)){
+                if (value[key] !== undefined) check(value[key], path + "." + key);
+            }
+            return;
+        }
+        const kind = type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+        throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+    };
+    for (const index of Object.keys(states)){
+        if (states[index] !== undefined) check(states[index], "useState #" + index);
+    }
+    for (const name of Object.keys(values)){
+        if (values[name] !== undefined) check(values[name], name);
+    }
+    const json = JSON.stringify({
+        state: states,
+        values
+    }).replace(/[<>&\u2028\u2029]/g, (c)=>"\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+    );
+    return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}
+const label = "Count";
+export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>0
+    );
+    const [name, setName] = $$serverState($$states, 1, ()=>"world"
+    );
+    return $$markup([
+        "<main>",
+        [
+            '<h1 data-bmr-hole="00c6c235:0"',
+            $$attr("title", name()),
+            ">",
+            [
+                "Hello <!--bmr:00c6c235:1-->",
+                $$child(name()),
+                "<!--/bmr:00c6c235:1-->!"
+            ],
+            "</h1>",
+            '<p data-bmr-hole="00c6c235:2"',
+            $$attr("class", $$class([
+                {
+                    odd: count() % 2 === 1
+                }
+            ])),
+            $$attr("data-label", label),
+            ">",
+            [
+                $$child(label),
+                ": <!--bmr:00c6c235:3-->",
+                $$child(count()),
+                "<!--/bmr:00c6c235:3-->"
+            ],
+            "</p><!--bmr:00c6c235:4-->",
+            $$child(count() > 2 && $$markup([
+                "<strong>",
+                [
+                    "Many ",
+                    $$child(count())
+                ],
+                "</strong>"
+            ])),
+            "<!--/bmr:00c6c235:4-->",
+            '<input data-bmr-hole="00c6c235:5"',
+            $$attr("value", name()),
+            ' data-bmr-input="00c6c235:0">',
+            '<button data-bmr-click="00c6c235:1">',
+            [
+                "+"
+            ],
+            "</button>"
+        ],
+        "</main>",
+        $$serialize("00c6c235", $$states, {}),
+        '<script type="module">function $$escape(value) {\n    return String(value).replace(/[&<>"\']/g, (c)=>({\n            "&": "&amp;",\n            "<": "&lt;",\n            ">": "&gt;",\n            \'"\': "&quot;",\n            "\'": "&#39;"\n        })[c]\n    );\n}\nfunction $$child(value) {\n    if (value == null || typeof value === "boolean") return "";\n    if (Array.isArray(value)) {\n        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);\n    }\n    return $$escape(value);\n}\nfunction $$markup(chunks) {\n    chunks[Symbol.for("boomer.markup")] = true;\n    return chunks;\n}\nfunction $$class(value) {\n    let classes;\n    if (Array.isArray(value)) {\n        classes = value.map($$class).filter(Boolean).join(" ");\n    } else if (value != null && typeof value === "object") {\n        classes = Object.keys(value).filter((key)=>value[key]\n        ).join(" ");\n    } else {\n        classes = value == null || typeof value === "boolean" ? "" : String(value);\n    }\n    return classes === "" ? null : classes;\n}\nlet $$observer = null;\nfunction $$useState(value) {\n    const observers = new Set();\n    const get = ()=>{\n        if ($$observer) observers.add($$observer);\n        return value;\n    };\n    const set = (next)=>{\n        next = typeof next === "function" ? next(value) : next;\n        if (Object.is(next, value)) return;\n        value = next;\n        for (const observer of [\n            ...observers\n        ])observer();\n    };\n    return [\n        get,\n        set\n    ];\n}\nconst $$captured = new Set([\n    "blur",\n    "error",\n    "focus",\n    "load",\n    "mouseenter",\n    "mouseleave",\n    "scroll"\n]);\nfunction $$delegate(module, events, handlers) {\n    const prefix = module + ":";\n    for (const event of events){\n        const marker = "data-bmr-" + event;\n        const captured = $$captured.has(event);\n        document.addEventListener(event, (e)=>{\n            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){\n                const id = node.getAttribute(marker);\n                if (id !== null && id.startsWith(prefix)) {\n                    handlers[id.slice(prefix.length)].call(node, e);\n                    if (e.cancelBubble) return;\n                }\n                if (captured) return;\n            }\n        }, captured);\n    }\n}\nfunction $$effect(compute, apply) {\n    let rendered = false;\n    const run = ()=>{\n        const observer = $$observer;\n        $$observer = run;\n        let value;\n        try {\n            value = compute();\n        } finally{\n            $$observer = observer;\n        }\n        if (rendered) apply(value);\n        rendered = true;\n    };\n    run();\n}\nlet $$holes;\nfunction $$bindText(id, value) {\n    if (!$$holes) {\n        $$holes = {};\n        const walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);\n        while(walker.nextNode()){\n            const match = /^bmr:(.+)$/.exec(walker.currentNode.data);\n            if (match) ($$holes[match[1]] ||= []).push(walker.currentNode);\n        }\n    }\n    const starts = $$holes[id] || [];\n    $$effect(value, (chunks)=>{\n        const html = [\n            chunks\n        ].flat(Infinity).join("");\n        for (const start of starts){\n            while(start.nextSibling && start.nextSibling.data !== "/bmr:" + id){\n                start.nextSibling.remove();\n            }\n            const template = document.createElement("template");\n            template.innerHTML = html;\n            start.after(template.content);\n        }\n    });\n}\nfunction $$bindAttr(id, name, value) {\n    const elements = document.querySelectorAll(\'[data-bmr-hole="\' + id + \'"]\');\n    $$effect(value, (v)=>{\n        const removed = v == null || v === false || typeof v === "function";\n        for (const element of elements){\n            if (removed) element.removeAttribute(name);\n            else element.setAttribute(name, v === true ? "" : String(v));\n            if (name === "value") element.value = removed ? "" : String(v);\n            if (name === "checked") element.checked = !removed;\n        }\n    });\n}\nlet $$payload;\nfunction $$resume(module, kind, key, initial) {\n    if ($$payload === undefined) {\n        const script = document.getElementById("bmr-payload:" + module);\n        $$payload = script ? JSON.parse(script.textContent) : {};\n    }\n    const values = $$payload[kind];\n    if (values && key in values) return values[key];\n    return initial ? initial() : undefined;\n}\nconst [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>0\n));\nconst [name, setName] = $$useState($$resume("00c6c235", "state", 1, ()=>"world"\n));\nconst $$handlers = [\n    (e)=>setName(e.target.value)\n    ,\n    ()=>setCount((n)=>n + 1\n        )\n];\n$$delegate("00c6c235", [\n    "click",\n    "input"\n], $$handlers);\n$$bindText("00c6c235:1", ()=>$$child(name())\n);\n$$bindText("00c6c235:3", ()=>$$child(count())\n);\n$$bindText("00c6c235:4", ()=>$$child(count() > 2 && $$markup([\n        "<strong>",\n        [\n            "Many ",\n            $$child(count())\n        ],\n        "</strong>"\n    ]))\n);\n$$bindAttr("00c6c235:0", "title", ()=>name()\n);\n$$bindAttr("00c6c235:2", "class", ()=>$$class([\n        {\n            odd: count() % 2 === 1\n        }\n    ])\n);\n$$bindAttr("00c6c235:5", "value", ()=>name()\n);\n</script>'
+    ]);
+}
@@ -0,0 +1,129 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+let $$observer = null;
+function $$useState(value) {
+    const observers = new Set();
+    const get = ()=>{
+        if ($$observer) observers.add($$observer);
+        return value;
+    };
+    const set = (next)=>{
+        next = typeof next === "function" ? next(value) : next;
+        if (Object.is(next, value)) return;
+        value = next;
+        for (const observer of [
+            ...observers
+        ])observer();
+    };
+    return [
+        get,
+        set
+    ];
+}
+const $$captured = new Set([
+    "blur",
+    "error",
+    "focus",
+    "load",
+    "mouseenter",
+    "mouseleave",
+    "scroll"
+]);
+function $$delegate(module, events, handlers) {
+    const prefix = module + ":";
+    for (const event of events){
+        const marker = "data-bmr-" + event;
+        const captured = $$captured.has(event);
+        document.addEventListener(event, (e)=>{
+            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){
+                const id = node.getAttribute(marker);
+                if (id !== null && id.startsWith(prefix)) {
+                    handlers[id.slice(prefix.length)].call(node, e);
+                    if (e.cancelBubble) return;
+                }
+                if (captured) return;
+            }
+        }, captured);
+    }
+}
+function $$effect(compute, apply) {
+    let rendered = false;
+    const run = ()=>{
+        const observer = $$observer;
+        $$observer = run;
+        let value;
+        try {
+            value = compute();
+        } finally{
+            $$observer = observer;
+        }
+        if (rendered) apply(value);
+        rendered = true;
+    };
+    run();
+}
+let $$holes;
+function $$bindText(id, value) {
+    if (!$$holes) {
+        $$holes = {};
+        const walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);
+        while(walker.nextNode()){
+            const match = /^bmr:(.+)$/.exec(walker.currentNode.data);
+            if (match) ($$holes[match[1]] ||= []).push(walker.currentNode);
+        }
+    }
+    const starts = $$holes[id] || [];
+    $$effect(value, (chunks)=>{
+        const html = [
+            chunks
+        ].flat(Infinity).join("");
+        for (const start of starts){
+            while(start.nextSibling && start.nextSibling.data !== "/bmr:" + id){
+                start.nextSibling.remove();
+            }
+            const template = document.createElement("template");
+            template.innerHTML = html;
+            start.after(template.content);
+        }
+    });
+}
+let $$payload;
+function $$resume(module, kind, key, initial) {
+    if ($$payload === undefined) {
+        const script = document.getElementById("bmr-payload:" + module);
+        $$payload = script ? JSON.parse(script.textContent) : {};
+    }
+    const values = $$payload[kind];
+    if (values && key in values) return values[key];
+    return initial ? initial() : undefined;
+}
+const user = $$resume("00c6c235", "values", "user"), step = $$resume("00c6c235", "values", "step");
+const [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>Date.now()
+));
+const [name, setName] = $$useState($$resume("00c6c235", "state", 1, ()=>user.name
+));
+const $$handlers = [
+    ()=>setCount(count() + step)
+];
+$$delegate("00c6c235", [
+    "click"
+], $$handlers);
+$$bindText("00c6c235:0", ()=>$$child(name())
+);
+$$bindText("00c6c235:1", ()=>$$child(count())
+);
@@ -0,0 +1,144 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$region(name, chunks) {
+    chunks[Symbol.for("boomer.region")] = name;
+    return chunks;
+}
+function $$document(chunks, bodyEnd) {
+    const head = [];
+    const regions = {};
+    const copy = (value)=>{
+        if (!Array.isArray(value)) return value;
+        if (value[Symbol.for("boomer.head")]) {
+            head.push(value.map(copy));
+            return "";
+        }
+        const chunks = value.map(copy);
+        const region = value[Symbol.for("boomer.region")];
+        if (region) regions[region] = chunks;
+        return chunks;
+    };
+    const html = copy(chunks);
+    (regions.head || html).unshift(...head);
+    (regions.body || html).push(...bodyEnd);
+    return [
+        "<!DOCTYPE html>",
+        html
+    ];
+}
+function $$serverState(states, index, initial) {
+    let value;
+    try {
+        value = initial();
+        states[index] = value;
+    } catch (error) {
+        if (!(error instanceof ReferenceError)) throw error;
+    }
+    return [
+        ()=>value
+        ,
+        ()=>{}
+    ];
+}
+function $$serialize(module, states, values) {
+    const check = (value, path)=>{
+        const type = typeof value;
+        if (value === null || type === "string" || type === "boolean") return;
+        if (type === "number" && Number.isFinite(value)) return;
+        if (Array.isArray(value)) {
+            value.forEach((item, index)=>check(item, path + "[" + index + "]")
+            );
+            return;
+        }
+        const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+        if (proto === Object.prototype || proto === null) {
+            for (const key of Object.keys(value)){
+                if (value[key] !== undefined) check(value[key], path + "." + key);
+            }
+            return;
+        }
+        const kind = type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+        throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+    };
+    for (const index of Object.keys(states)){
+        if (states[index] !== undefined) check(states[index], "useState #" + index);
+    }
+    for (const name of Object.keys(values)){
+        if (values[name] !== undefined) check(values[name], name);
+    }
+    const json = JSON.stringify({
+        state: states,
+        values
+    }).replace(/[<>&\u2028\u2029]/g, (c)=>"\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+    );
+    return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}
+const user = {
+    name: "</script><script>alert(1)</script>"
+};
+const step = 2;
+const unused = "not sent";
+export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>Date.now()
+    );
+    const [name, setName] = $$serverState($$states, 1, ()=>user.name
+    );
+    return $$markup($$document([
+        "<html>",
+        [
+            "<head>",
+            [
+                $$region("head", [])
+            ],
+            "</head>",
+            "<body>",
+            [
+                $$region("body", [
+                    "<p>",
+                    [
+                        "<!--bmr:00c6c235:0-->",
+                        $$child(name()),
+                        "<!--/bmr:00c6c235:0-->: <!--bmr:00c6c235:1-->",
+                        $$child(count()),
+                        "<!--/bmr:00c6c235:1-->"
+                    ],
+                    "</p>",
+                    '<button data-bmr-click="00c6c235:0">',
+                    [
+                        "+",
+                        $$child(step)
+                    ],
+                    "</button>"
+                ])
+            ],
+            "</body>"
+        ],
+        "</html>"
+    ], [
+        $$serialize("00c6c235", $$states, {
+            user,
+            step
+        }),
+        '<script type="module">function $$escape(value) {\n    return String(value).replace(/[&<>"\']/g, (c)=>({\n            "&": "&amp;",\n            "<": "&lt;",\n            ">": "&gt;",\n            \'"\': "&quot;",\n            "\'": "&#39;"\n        })[c]\n    );\n}\nfunction $$child(value) {\n    if (value == null || typeof value === "boolean") return "";\n    if (Array.isArray(value)) {\n        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);\n    }\n    return $$escape(value);\n}\nlet $$observer = null;\nfunction $$useState(value) {\n    const observers = new Set();\n    const get = ()=>{\n        if ($$observer) observers.add($$observer);\n        return value;\n    };\n    const set = (next)=>{\n        next = typeof next === "function" ? next(value) : next;\n        if (Object.is(next, value)) return;\n        value = next;\n        for (const observer of [\n            ...observers\n        ])observer();\n    };\n    return [\n        get,\n        set\n    ];\n}\nconst $$captured = new Set([\n    "blur",\n    "error",\n    "focus",\n    "load",\n    "mouseenter",\n    "mouseleave",\n    "scroll"\n]);\nfunction $$delegate(module, events, handlers) {\n    const prefix = module + ":";\n    for (const event of events){\n        const marker = "data-bmr-" + event;\n        const captured = $$captured.has(event);\n        document.addEventListener(event, (e)=>{\n            for(let node = e.target; node && node.nodeType === 1; node = node.parentNode){\n                const id = node.getAttribute(marker);\n                if (id !== null && id.startsWith(prefix)) {\n                    handlers[id.slice(prefix.length)].call(node, e);\n                    if (e.cancelBubble) return;\n                }\n                if (captured) return;\n            }\n        }, captured);\n    }\n}\nfunction $$effect(compute, apply) {\n    let rendered = false;\n    const run = ()=>{\n        const observer = $$observer;\n        $$observer = run;\n        let value;\n        try {\n            value = compute();\n        } finally{\n            $$observer = observer;\n        }\n        if (rendered) apply(value);\n        rendered = true;\n    };\n    run();\n}\nlet $$holes;\nfunction $$bindText(id, value) {\n    if (!$$holes) {\n        $$holes = {};\n        const walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);\n        while(walker.nextNode()){\n            const match = /^bmr:(.+)$/.exec(walker.currentNode.data);\n            if (match) ($$holes[match[1]] ||= []).push(walker.currentNode);\n        }\n    }\n    const starts = $$holes[id] || [];\n    $$effect(value, (chunks)=>{\n        const html = [\n            chunks\n        ].flat(Infinity).join("");\n        for (const start of starts){\n            while(start.nextSibling && start.nextSibling.data !== "/bmr:" + id){\n                start.nextSibling.remove();\n            }\n            const template = document.createElement("template");\n            template.innerHTML = html;\n            start.after(template.content);\n        }\n    });\n}\nlet $$payload;\nfunction $$resume(module, kind, key, initial) {\n    if ($$payload === undefined) {\n        const script = document.getElementById("bmr-payload:" + module);\n        $$payload = script ? JSON.parse(script.textContent) : {};\n    }\n    const values = $$payload[kind];\n    if (values && key in values) return values[key];\n    return initial ? initial() : undefined;\n}\nconst user = $$resume("00c6c235", "values", "user"), step = $$resume("00c6c235", "values", "step");\nconst [count, setCount] = $$useState($$resume("00c6c235", "state", 0, ()=>Date.now()\n));\nconst [name, setName] = $$useState($$resume("00c6c235", "state", 1, ()=>user.name\n));\nconst $$handlers = [\n    ()=>setCount(count() + step)\n];\n$$delegate("00c6c235", [\n    "click"\n], $$handlers);\n$$bindText("00c6c235:0", ()=>$$child(name())\n);\n$$bindText("00c6c235:1", ()=>$$child(count())\n);\n</script>'
+    ]));
+}
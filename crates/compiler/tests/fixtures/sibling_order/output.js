@@ -0,0 +1,51 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const hello = "Hello";
+const world = "World";
+export function render(props = {}) {
+    return $$markup([
+        "<div>",
+        [
+            "<h1>",
+            [
+                $$child(hello)
+            ],
+            "</h1>",
+            "<h2>",
+            [
+                $$child(world),
+                "<span>",
+                [
+                    "hello"
+                ],
+                "</span>"
+            ],
+            "</h2>",
+            "<button>",
+            [
+                "Click Me"
+            ],
+            "</button>"
+        ],
+        "</div>"
+    ]);
+}
@@ -0,0 +1,50 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$slot(props, name, fallback) {
+    const content = name == null ? props.children : props.slots && props.slots[name];
+    return $$child(content == null ? fallback : content);
+}
+export function render(props = {}) {
+    return $$markup([
+        '<div class="layout">',
+        [
+            "<header>",
+            [
+                $$slot(props, "header", $$markup([
+                    "Default title"
+                ]))
+            ],
+            "</header>",
+            "<main>",
+            [
+                $$slot(props, null)
+            ],
+            "</main>",
+            "<footer>",
+            [
+                $$slot(props, "footer")
+            ],
+            "</footer>"
+        ],
+        "</div>"
+    ]);
+}
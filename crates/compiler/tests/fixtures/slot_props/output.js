@@ -0,0 +1,101 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$slot(props, name, fallback) {
+    const content = name == null ? props.children : props.slots && props.slots[name];
+    return $$child(content == null ? fallback : content);
+}
+function Box($$props) {
+    let { title  } = $$props;
+    return ($$markup([
+        "<section>",
+        [
+            "<h2>",
+            [
+                $$child(title)
+            ],
+            "</h2>",
+            $$slot($$props, null)
+        ],
+        "</section>"
+    ]));
+}
+const Panel = ($$props = {})=>{
+    let { heading  } = $$props;
+    return ($$markup([
+        "<aside>",
+        [
+            "<h3>",
+            [
+                $$child(heading)
+            ],
+            "</h3>",
+            $$slot($$props, "footer", $$markup([
+                "No footer"
+            ]))
+        ],
+        "</aside>"
+    ]));
+};
+function Card(card) {
+    return $$markup([
+        '<div class="card">',
+        [
+            $$slot(card, null)
+        ],
+        "</div>"
+    ]);
+}
+export function render(props = {}) {
+    return $$markup([
+        "<main>",
+        [
+            $$child(Box({
+                title: "Hello",
+                children: $$markup([
+                    "<p>",
+                    [
+                        "Boxed"
+                    ],
+                    "</p>"
+                ])
+            })),
+            $$child(Panel({
+                heading: "Side",
+                slots: {
+                    footer: $$markup([
+                        "<small>",
+                        [
+                            "Fine print"
+                        ],
+                        "</small>"
+                    ])
+                }
+            })),
+            $$child(Card({
+                children: $$markup([
+                    "Carded"
+                ])
+            }))
+        ],
+        "</main>"
+    ]);
+}
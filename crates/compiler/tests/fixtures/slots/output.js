@@ -0,0 +1,73 @@
+import { render as Layout } from "./layout.js";
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const user = "<Ada>";
+function Card({ title , children  }) {
+    return $$markup([
+        "<section>",
+        [
+            "<h2>",
+            [
+                $$child(title)
+            ],
+            "</h2>",
+            $$child(children)
+        ],
+        "</section>"
+    ]);
+}
+export function render(props = {}) {
+    return $$markup([
+        $$child(Layout({
+            children: $$markup([
+                $$child(Card({
+                    title: "Card",
+                    children: $$markup([
+                        "<p>",
+                        [
+                            "Inside ",
+                            $$child(user)
+                        ],
+                        "</p>"
+                    ])
+                }))
+            ]),
+            slots: {
+                header: $$markup([
+                    "<h1>",
+                    [
+                        "Hello ",
+                        $$child(user)
+                    ],
+                    "</h1>"
+                ]),
+                footer: $$markup([
+                    "<span>",
+                    [
+                        "\xa9"
+                    ],
+                    "</span>"
+                ])
+            }
+        }))
+    ]);
+}
@@ -0,0 +1,192 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$attr(name, value) {
+    if (value == null || value === false || typeof value === "function") return "";
+    if (value === true) return " " + name;
+    return " " + name + '="' + $$escape(value) + '"';
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$spreadAttrs(attrs) {
+    if (attrs == null) return "";
+    const merged = new Map();
+    for (const key of Object.keys(attrs)){
+        const value = attrs[key];
+        if (key === "children" || /^on/i.test(key)) continue;
+        if (!/^[^\s"'<>\/=]+$/.test(key)) continue;
+        const name = key === "className" ? "class" : key === "htmlFor" ? "for" : key;
+        merged.delete(name);
+        if (value === undefined) continue;
+        merged.set(name, name === "class" ? $$class(value) : name === "style" ? $$style(value) : value);
+    }
+    let html = "";
+    for (const [name, value] of merged)html += $$attr(name, value);
+    return html;
+}
+function $$class(value) {
+    let classes;
+    if (Array.isArray(value)) {
+        classes = value.map($$class).filter(Boolean).join(" ");
+    } else if (value != null && typeof value === "object") {
+        classes = Object.keys(value).filter((key)=>value[key]
+        ).join(" ");
+    } else {
+        classes = value == null || typeof value === "boolean" ? "" : String(value);
+    }
+    return classes === "" ? null : classes;
+}
+const $$unitless = new Set([
+    "animationIterationCount",
+    "aspectRatio",
+    "borderImageOutset",
+    "borderImageSlice",
+    "borderImageWidth",
+    "boxFlex",
+    "boxFlexGroup",
+    "boxOrdinalGroup",
+    "columnCount",
+    "columns",
+    "fillOpacity",
+    "flex",
+    "flexGrow",
+    "flexNegative",
+    "flexOrder",
+    "flexPositive",
+    "flexShrink",
+    "floodOpacity",
+    "fontWeight",
+    "gridArea",
+    "gridColumn",
+    "gridColumnEnd",
+    "gridColumnStart",
+    "gridRow",
+    "gridRowEnd",
+    "gridRowStart",
+    "lineClamp",
+    "lineHeight",
+    "opacity",
+    "order",
+    "orphans",
+    "scale",
+    "stopOpacity",
+    "strokeDasharray",
+    "strokeDashoffset",
+    "strokeMiterlimit",
+    "strokeOpacity",
+    "strokeWidth",
+    "tabSize",
+    "widows",
+    "zIndex",
+    "zoom"
+]);
+function $$style(value) {
+    if (value == null || typeof value === "boolean") return null;
+    if (typeof value !== "object") return String(value);
+    const declarations = [];
+    for (const key of Object.keys(value)){
+        const v = value[key];
+        if (v == null || typeof v === "boolean" || String(v).trim() === "") continue;
+        const property = key.startsWith("--") ? key : key.replace(/^ms(?=[A-Z])/, "-ms").replace(/[A-Z]/g, (c)=>"-" + c.toLowerCase()
+        );
+        const unitless = $$unitless.has(key.replace(/^(?:Webkit|Moz|ms|O)([A-Z])/, (_, c)=>c.toLowerCase()
+        ));
+        const css = typeof v === "number" && v !== 0 && !unitless ? v + "px" : String(v).trim();
+        declarations.push(property + ":" + css);
+    }
+    return declarations.length === 0 ? null : declarations.join(";");
+}
+const attrs = {
+    id: "main",
+    className: "box",
+    title: '"quoted" & <tagged>',
+    hidden: false,
+    open: true,
+    onClick: ()=>{},
+    onmouseover: "alert(1)",
+    "bad name": "x",
+    missing: undefined
+};
+const items = [
+    "<a>",
+    $$markup([
+        "<b>",
+        [
+            "bold"
+        ],
+        "</b>"
+    ]),
+    3
+];
+const link = {
+    href: "/later",
+    className: "link",
+    style: {
+        marginTop: 4
+    }
+};
+function Card({ title , ...rest }) {
+    return $$markup([
+        "<div",
+        $$spreadAttrs({
+            ...rest
+        }),
+        ">",
+        [
+            $$child(title)
+        ],
+        "</div>"
+    ]);
+}
+export function render(props = {}) {
+    return $$markup([
+        "<main>",
+        [
+            "<section",
+            $$spreadAttrs({
+                "data-first": "1",
+                ...attrs,
+                "data-last": "2"
+            }),
+            ">",
+            [
+                ...$$child(items)
+            ],
+            "</section>",
+            $$child(Card({
+                title: "Card",
+                ...attrs,
+                id: "override"
+            })),
+            "<a",
+            $$spreadAttrs({
+                href: "/first",
+                class: "first",
+                ...link,
+                title: undefined
+            }),
+            ">",
+            [
+                "Later wins"
+            ],
+            "</a>"
+        ],
+        "</main>"
+    ]);
+}
@@ -0,0 +1,184 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$attr(name, value) {
+    if (value == null || value === false || typeof value === "function") return "";
+    if (value === true) return " " + name;
+    return " " + name + '="' + $$escape(value) + '"';
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$class(value) {
+    let classes;
+    if (Array.isArray(value)) {
+        classes = value.map($$class).filter(Boolean).join(" ");
+    } else if (value != null && typeof value === "object") {
+        classes = Object.keys(value).filter((key)=>value[key]
+        ).join(" ");
+    } else {
+        classes = value == null || typeof value === "boolean" ? "" : String(value);
+    }
+    return classes === "" ? null : classes;
+}
+const $$unitless = new Set([
+    "animationIterationCount",
+    "aspectRatio",
+    "borderImageOutset",
+    "borderImageSlice",
+    "borderImageWidth",
+    "boxFlex",
+    "boxFlexGroup",
+    "boxOrdinalGroup",
+    "columnCount",
+    "columns",
+    "fillOpacity",
+    "flex",
+    "flexGrow",
+    "flexNegative",
+    "flexOrder",
+    "flexPositive",
+    "flexShrink",
+    "floodOpacity",
+    "fontWeight",
+    "gridArea",
+    "gridColumn",
+    "gridColumnEnd",
+    "gridColumnStart",
+    "gridRow",
+    "gridRowEnd",
+    "gridRowStart",
+    "lineClamp",
+    "lineHeight",
+    "opacity",
+    "order",
+    "orphans",
+    "scale",
+    "stopOpacity",
+    "strokeDasharray",
+    "strokeDashoffset",
+    "strokeMiterlimit",
+    "strokeOpacity",
+    "strokeWidth",
+    "tabSize",
+    "widows",
+    "zIndex",
+    "zoom"
+]);
+function $$style(value) {
+    if (value == null || typeof value === "boolean") return null;
+    if (typeof value !== "object") return String(value);
+    const declarations = [];
+    for (const key of Object.keys(value)){
+        const v = value[key];
+        if (v == null || typeof v === "boolean" || String(v).trim() === "") continue;
+        const property = key.startsWith("--") ? key : key.replace(/^ms(?=[A-Z])/, "-ms").replace(/[A-Z]/g, (c)=>"-" + c.toLowerCase()
+        );
+        const unitless = $$unitless.has(key.replace(/^(?:Webkit|Moz|ms|O)([A-Z])/, (_, c)=>c.toLowerCase()
+        ));
+        const css = typeof v === "number" && v !== 0 && !unitless ? v + "px" : String(v).trim();
+        declarations.push(property + ":" + css);
+    }
+    return declarations.length === 0 ? null : declarations.join(";");
+}
+const active = true;
+const color = "red";
+const size = 12;
+const extra = [
+    "x",
+    {
+        y: false,
+        z: true
+    }
+];
+export function render(props = {}) {
+    return $$markup([
+        "<div>",
+        [
+            '<p style="color:red;margin-top:4px;line-height:1.5;z-index:0;-ms-transform:none;-webkit-box-flex:1;--gap:2px">',
+            [
+                "static"
+            ],
+            "</p>",
+            "<p",
+            $$attr("style", $$style({
+                color,
+                fontSize: size
+            })),
+            ">",
+            [
+                "dynamic"
+            ],
+            "</p>",
+            "<p",
+            $$attr("style", $$style(`color: ${color}`)),
+            ">",
+            [
+                "string"
+            ],
+            "</p>",
+            "<p>",
+            [
+                "empty"
+            ],
+            "</p>",
+            '<p class="btn btn-primary big">',
+            [
+                "static"
+            ],
+            "</p>",
+            "<p",
+            $$attr("class", $$class([
+                "btn round",
+                active && "active",
+                {
+                    disabled: !active
+                }
+            ])),
+            ">",
+            [
+                "mixed"
+            ],
+            "</p>",
+            "<p",
+            $$attr("class", $$class([
+                {
+                    on: active
+                }
+            ])),
+            ">",
+            [
+                "object"
+            ],
+            "</p>",
+            "<p",
+            $$attr("class", $$class(extra)),
+            ">",
+            [
+                "variable"
+            ],
+            "</p>",
+            "<p",
+            $$attr("class", $$class([
+                "a",
+                [
+                    ...extra
+                ]
+            ])),
+            ">",
+            [
+                "spread"
+            ],
+            "</p>"
+        ],
+        "</div>"
+    ]);
+}
@@ -0,0 +1,73 @@
+import * as Icons from "./icons.js";
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const UI = {
+    Forms: {
+        Button ({ children  }) {
+            return $$markup([
+                "<button>",
+                [
+                    $$child(children)
+                ],
+                "</button>"
+            ]);
+        }
+    }
+};
+export function render(props = {}) {
+    return $$markup([
+        "<div>",
+        [
+            '<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 10 10">',
+            [
+                '<use xlink:href="#star">',
+                [],
+                "</use>",
+                '<path d="M0 0L10 10" stroke-width="2" stroke-linecap="round">',
+                [],
+                "</path>",
+                '<svg:rect width="10" height="10">',
+                [],
+                "</svg:rect>"
+            ],
+            "</svg>",
+            '<math xmlns="http://www.w3.org/1998/Math/MathML">',
+            [
+                "<mi>",
+                [
+                    "x"
+                ],
+                "</mi>"
+            ],
+            "</math>",
+            $$child(Icons.Star({
+                size: 16
+            })),
+            $$child(UI.Forms.Button({
+                children: $$markup([
+                    "Save"
+                ])
+            }))
+        ],
+        "</div>"
+    ]);
+}
@@ -0,0 +1,44 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$attr(name, value) {
+    if (value == null || value === false || typeof value === "function") return "";
+    if (value === true) return " " + name;
+    return " " + name + '="' + $$escape(value) + '"';
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const src = "/logo.png";
+const name = "email";
+export function render(props = {}) {
+    return $$markup([
+        "<form>",
+        [
+            '<meta charset="utf-8">',
+            "<img",
+            $$attr("src", src),
+            ' alt="Logo">',
+            "<br>",
+            "<label>",
+            [
+                "Email",
+                "<br>",
+                '<input type="email"',
+                $$attr("name", name),
+                " required>"
+            ],
+            "</label>",
+            "<hr>"
+        ],
+        "</form>"
+    ]);
+}
@@ -0,0 +1,65 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+const code = "fn main() {}";
+export function render(props = {}) {
+    return $$markup([
+        "<div>",
+        [
+            "<p>",
+            [
+                "Lorem   ipsum dolor sit amet"
+            ],
+            "</p>",
+            "<p>",
+            [
+                "  keeps ",
+                "<b>",
+                [
+                    "inner"
+                ],
+                "</b> ",
+                "<i>",
+                [
+                    "spaces"
+                ],
+                "</i>  "
+            ],
+            "</p>",
+            "<p>",
+            [],
+            "</p>",
+            "<pre>",
+            [
+                "\n  first line\n      ",
+                $$child(code),
+                "\n  last line\n  "
+            ],
+            "</pre>",
+            "<textarea>",
+            [
+                "\n    indented\n  "
+            ],
+            "</textarea>"
+        ],
+        "</div>"
+    ]);
+}
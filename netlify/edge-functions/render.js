@@ -25,5 +25,7 @@ export default () => {
     },
   });
 
-  return new Response(readableStream);
+  return new Response(readableStream, {
+    headers: { "content-type": "text/html; charset=utf-8" },
+  });
 };
@@ -7,12 +7,18 @@ client: {
   const [count, setCount] = useState(0);
 }
 
-<div>
-  <h1>{hello}</h1>
-  <h2>
-    {world}
-    <span>hello</span>
-  </h2>
-  {/* <button onClick={() => setCount(count() + 1)}>{count()}</button> */}
-  <button>Click Me</button>
-</div>;
+<html lang="en">
+  <head>
+    <title>Hello</title>
+  </head>
+  <body>
+    <div>
+      <h1>{hello}</h1>
+      <h2>
+        {world}
+        <span>hello</span>
+      </h2>
+      <button onClick={() => setCount(count() + 1)}>Clicked {count()} times</button>
+    </div>
+  </body>
+</html>;
@@ -1,30 +1,153 @@
+function $$escape(value) {
+    return String(value).replace(/[&<>"']/g, (c)=>({
+            "&": "&amp;",
+            "<": "&lt;",
+            ">": "&gt;",
+            '"': "&quot;",
+            "'": "&#39;"
+        })[c]
+    );
+}
+function $$child(value) {
+    if (value == null || typeof value === "boolean") return "";
+    if (Array.isArray(value)) {
+        return value[Symbol.for("boomer.markup")] ? value : value.map($$child);
+    }
+    return $$escape(value);
+}
+function $$markup(chunks) {
+    chunks[Symbol.for("boomer.markup")] = true;
+    return chunks;
+}
+function $$region(name, chunks) {
+    chunks[Symbol.for("boomer.region")] = name;
+    return chunks;
+}
+function $$document(chunks, bodyEnd) {
+    const head = [];
+    const regions = {};
+    const copy = (value)=>{
+        if (!Array.isArray(value)) return value;
+        if (value[Symbol.for("boomer.head")]) {
+            head.push(value.map(copy));
+            return "";
+        }
+        const chunks = value.map(copy);
+        const region = value[Symbol.for("boomer.region")];
+        if (region) regions[region] = chunks;
+        return chunks;
+    };
+    const html = copy(chunks);
+    (regions.head || html).unshift(...head);
+    (regions.body || html).push(...bodyEnd);
+    return [
+        "<!DOCTYPE html>",
+        html
+    ];
+}
+function $$serverState(states, index, initial) {
+    let value;
+    try {
+        value = initial();
+        states[index] = value;
+    } catch (error) {
+        if (!(error instanceof ReferenceError)) throw error;
+    }
+    return [
+        ()=>value
+        ,
+        ()=>{}
+    ];
+}
+function $$serialize(module, states, values) {
+    const check = (value, path)=>{
+        const type = typeof value;
+        if (value === null || type === "string" || type === "boolean") return;
+        if (type === "number" && Number.isFinite(value)) return;
+        if (Array.isArray(value)) {
+            value.forEach((item, index)=>check(item, path + "[" + index + "]")
+            );
+            return;
+        }
+        const proto = type === "object" ? Object.getPrototypeOf(value) : undefined;
+        if (proto === Object.prototype || proto === null) {
+            for (const key of Object.keys(value)){
+                if (value[key] !== undefined) check(value[key], path + "." + key);
+            }
+            return;
+        }
+        const kind = type === "number" ? String(value) : proto && proto.constructor ? proto.constructor.name : type;
+        throw new TypeError(path + " is " + kind + ", the client can only resume JSON values");
+    };
+    for (const index of Object.keys(states)){
+        if (states[index] !== undefined) check(states[index], "useState #" + index);
+    }
+    for (const name of Object.keys(values)){
+        if (values[name] !== undefined) check(values[name], name);
+    }
+    const json = JSON.stringify({
+        state: states,
+        values
+    }).replace(/[<>&\u2028\u2029]/g, (c)=>"\\u" + c.charCodeAt(0).toString(16).padStart(4, "0")
+    );
+    return '<script type="application/json" id="bmr-payload:' + module + '">' + json + "</script>";
+}
 const hello = "Hello";
 const world = "World";
-export function render() {
-    return [
-        "<button>",
+export function render(props = {}) {
+    const $$states = {};
+    const [count, setCount] = $$serverState($$states, 0, ()=>0
+    );
+    return $$markup($$document([
+        '<html lang="en">',
         [
-            `Click Me`
+            "<head>",
+            [
+                $$region("head", [
+                    "<title>",
+                    [
+                        "Hello"
+                    ],
+                    "</title>"
+                ])
+            ],
+            "</head>",
+            "<body>",
+            [
+                $$region("body", [
+                    "<div>",
+                    [
+                        "<h1>",
+                        [
+                            $$child(hello)
+                        ],
+                        "</h1>",
+                        "<h2>",
+                        [
+                            $$child(world),
+                            "<span>",
+                            [
+                                "hello"
+                            ],
+                            "</span>"
+                        ],
+                        "</h2>",
+                        '<button data-bmr-click="5f4032c2:0">',
+                        [
+                            "Clicked <!--bmr:5f4032c2:0-->",
+                            $$child(count()),
+                            "<!--/bmr:5f4032c2:0--> times"
+                        ],
+                        "</button>"
+                    ],
+                    "</div>"
+                ])
+            ],
+            "</body>"
         ],
-        "</button>",
-        "<div>",
-        [
-            `
-
-  `,
-            `
-
-  `,
-            `
-
-  `,
-            `
-
-  `,
-            `
-
-`
-        ],
-        "</div>"
-    ];
+        "</html>"
+    ], [
+        $$serialize("5f4032c2", $$states, {}),
+        '<script type="module" src="/test_data/output.client.js"></script>'
+    ]));
 }